@@ -1 +1,4 @@
+pub mod prelude;
 pub mod splitter;
+
+pub use splitter::from_char::chunk;
@@ -0,0 +1,39 @@
+//! The common splitting types, for `use r4g_base::prelude::*`.
+//!
+//! Only the general-purpose splitters, their option types, the `Splitter` trait and the
+//! `StrSplitExt` methods on `str` live here. Format-specific splitters (markdown, HTML, code,
+//! bytes) are re-exported from `r4g_base::splitter`.
+//!
+//! ```
+//! use r4g_base::prelude::*;
+//!
+//! fn sizes<S: Splitter>(splitter: &S) -> Vec<usize> {
+//!     splitter.split_indices().iter().map(|r| r.len()).collect()
+//! }
+//!
+//! let text = "One. Two two.";
+//! assert_eq!(SentenceSplitter::new(text).split(), vec!["One.", "Two two."]);
+//! assert_eq!(sizes(&Utf8Splitter::new(text, 5, 0, true)), vec![5, 5, 3]);
+//! assert_eq!(Splitter::chunk_count(&SentenceSplitter::new(text)), 2);
+//! ```
+
+pub use crate::splitter::{
+    sliding_windows, Chunk, SentenceSplitter, SeparatorPolicy, SliceSplitter, Splitter,
+    StrSplitExt, Tail, TrimPolicy, Utf8Splitter,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prelude_covers_basic_use() {
+        let chars = Utf8Splitter::with_tail("abcdefg", 3, 0, Tail::Keep)
+            .trim(TrimPolicy::Both)
+            .split();
+        assert_eq!(chars, vec!["abc", "def", "g"]);
+        let items = SliceSplitter::new(&[1, 2, 3, 4], 2, 0, false).split();
+        assert_eq!(items, vec![&[1, 2][..], &[3, 4][..]]);
+        assert_eq!(sliding_windows(4, 2, 0, Tail::Drop).count(), 2);
+    }
+}
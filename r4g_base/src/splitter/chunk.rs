@@ -0,0 +1,444 @@
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+use crate::splitter::utils::hash_chunk;
+
+/// A chunk of text borrowed from the input, together with its byte range in the input and
+/// optional metadata `M` (none by default).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk<'a, M = ()> {
+    /// The chunk text, borrowed from the input.
+    pub text: &'a str,
+    /// Byte range of `text` within the input.
+    pub range: Range<usize>,
+    /// Number of characters in `text`, counted once when the chunk is built.
+    pub char_len: usize,
+    /// Number of bytes in `text` (the length of `range`).
+    pub byte_len: usize,
+    /// Metadata carried along with the chunk, e.g. the source document's URL and author.
+    pub meta: M,
+}
+
+impl<'a> Chunk<'a> {
+    pub fn new(input: &'a str, range: Range<usize>) -> Self {
+        let text = &input[range.clone()];
+        Self {
+            text,
+            char_len: text.chars().count(),
+            byte_len: range.len(),
+            range,
+            meta: (),
+        }
+    }
+}
+
+impl<'a, M> Chunk<'a, M> {
+    /// The same chunk carrying `meta` instead.
+    pub fn with_meta<N>(self, meta: N) -> Chunk<'a, N> {
+        Chunk {
+            text: self.text,
+            range: self.range,
+            char_len: self.char_len,
+            byte_len: self.byte_len,
+            meta,
+        }
+    }
+
+    /// A hash of the chunk text that is identical across runs, platforms and crate versions.
+    ///
+    /// The algorithm is 64-bit FNV-1a over the UTF-8 bytes of `text` (see `stable_hash`), so
+    /// equal text always hashes equal regardless of where it occurs in the input.
+    pub fn content_hash(&self) -> u64 {
+        hash_chunk(self.text)
+    }
+}
+
+/// A chunk that shares ownership of its source text instead of borrowing or copying it.
+///
+/// Every `ArcChunk` cut from one source holds a clone of the same `Arc<str>`, so the chunks are
+/// `'static` while the text is stored once however much they overlap. Derefs to the chunk text.
+#[derive(Clone)]
+pub struct ArcChunk {
+    source: Arc<str>,
+    range: Range<usize>,
+}
+
+impl ArcChunk {
+    /// The chunk covering `range` of `source`.
+    ///
+    /// Panics if `range` is out of bounds or not on character boundaries.
+    pub fn new(source: Arc<str>, range: Range<usize>) -> Self {
+        assert!(
+            source.get(range.clone()).is_some(),
+            "range {range:?} is not a valid slice of the source"
+        );
+        Self { source, range }
+    }
+
+    /// The chunk text.
+    pub fn as_str(&self) -> &str {
+        &self.source[self.range.clone()]
+    }
+
+    /// Byte range of the chunk within the source.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The shared source text the chunk was cut from.
+    pub fn source(&self) -> &Arc<str> {
+        &self.source
+    }
+}
+
+impl Deref for ArcChunk {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ArcChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcChunk")
+            .field("text", &self.as_str())
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl PartialEq for ArcChunk {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ArcChunk {}
+
+impl PartialEq<str> for ArcChunk {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ArcChunk {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Serializes as the chunk text alone, like a `String`; the source and range are not written.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ArcChunk {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes from a string, as a chunk spanning the whole of its own new source.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ArcChunk {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let len = text.len();
+        Ok(Self::new(Arc::from(text), 0..len))
+    }
+}
+
+/// A window of a shared `Arc<[T]>`: the slice counterpart of `ArcChunk`, since an `Arc<[T]>`
+/// cannot be sub-sliced in place.
+///
+/// Making a `SharedSlice` costs one atomic reference-count increment and a range, however long
+/// the window; `to_vec` would copy every element. The trade-off is that the whole source stays
+/// alive as long as any window of it does. Derefs to the window's elements.
+pub struct SharedSlice<T> {
+    source: Arc<[T]>,
+    range: Range<usize>,
+}
+
+impl<T> SharedSlice<T> {
+    /// The window covering `range` of `source`.
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn new(source: Arc<[T]>, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= source.len(),
+            "range {range:?} is out of bounds for a source of length {}",
+            source.len()
+        );
+        Self { source, range }
+    }
+
+    /// The window's elements.
+    pub fn as_slice(&self) -> &[T] {
+        &self.source[self.range.clone()]
+    }
+
+    /// Element range of the window within the source.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The shared source the window was cut from.
+    pub fn source(&self) -> &Arc<[T]> {
+        &self.source
+    }
+}
+
+impl<T> Clone for SharedSlice<T> {
+    fn clone(&self) -> Self {
+        Self {
+            source: Arc::clone(&self.source),
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<T> Deref for SharedSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedSlice")
+            .field("items", &self.as_slice())
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SharedSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for SharedSlice<T> {}
+
+impl<T: PartialEq> PartialEq<[T]> for SharedSlice<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq> PartialEq<&[T]> for SharedSlice<T> {
+    fn eq(&self, other: &&[T]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+/// A self-contained, owned record of one chunk, for shipping chunks to another process.
+///
+/// Field names are snake_case and part of the stable interface; `to_json` writes them in
+/// declaration order. All fields are owned, so records outlive the input they were cut from.
+///
+/// With the `serde` feature the record implements `Serialize` and `Deserialize` with the same
+/// field names, for any serde format; `to_json` needs no feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SerializableChunk {
+    /// The document the chunk was cut from.
+    pub doc_id: String,
+    /// Position of the chunk among the chunks of the document.
+    pub index: usize,
+    /// `chunk_id(doc_id, text)` as 32 lowercase hex digits.
+    pub chunk_id: String,
+    /// Start of the chunk in the input, in bytes.
+    pub start: usize,
+    /// End of the chunk in the input, in bytes (exclusive).
+    pub end: usize,
+    /// Number of characters in `text`.
+    pub char_len: usize,
+    /// Whether this is a partial window kept at the end by the tail policy.
+    pub is_tail: bool,
+    /// The chunk text.
+    pub text: String,
+}
+
+impl SerializableChunk {
+    /// The record as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"doc_id\":");
+        push_json_string(&mut out, &self.doc_id);
+        out.push_str(&format!(
+            ",\"index\":{},\"chunk_id\":\"{}\",\"start\":{},\"end\":{},\"char_len\":{},\"is_tail\":{},\"text\":",
+            self.index, self.chunk_id, self.start, self.end, self.char_len, self.is_tail
+        ));
+        push_json_string(&mut out, &self.text);
+        out.push('}');
+        out
+    }
+}
+
+/// Appends `s` to `out` as a quoted JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// 128-bit FNV-1a offset basis.
+const FNV128_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+/// 128-bit FNV-1a prime.
+const FNV128_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// A content-addressed identifier for a chunk of a document, stable across runs, platforms and
+/// crate versions.
+///
+/// The algorithm is pinned: 128-bit FNV-1a (offset basis `0x6c62272e07bb014262b821756295c58d`,
+/// prime `0x1000000000000000000013b`) over the length of `doc_id` in bytes as a little-endian
+/// `u64`, then the UTF-8 bytes of `doc_id`, then the UTF-8 bytes of `chunk_text`. The length
+/// prefix keeps `("ab", "c")` and `("a", "bc")` apart. The same text in the same document always
+/// gets the same id, wherever it occurs. Changing any of this is a breaking change; the vectors in
+/// `testdata/chunk_id_vectors.txt` pin it.
+pub fn chunk_id(doc_id: &str, chunk_text: &str) -> u128 {
+    let prefix = (doc_id.len() as u64).to_le_bytes();
+    let bytes = prefix
+        .iter()
+        .chain(doc_id.as_bytes())
+        .chain(chunk_text.as_bytes());
+    let mut h = FNV128_OFFSET;
+    for &b in bytes {
+        h ^= u128::from(b);
+        h = h.wrapping_mul(FNV128_PRIME);
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_hash_depends_only_on_text() {
+        let s = "abcabc";
+        let a = Chunk::new(s, 0..3);
+        let b = Chunk::new(s, 3..6);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_ne!(a.content_hash(), Chunk::new(s, 1..4).content_hash());
+    }
+
+    #[test]
+    fn lengths_match_text() {
+        let s = "añ😀b\u{301}c";
+        let c = Chunk::new(s, 1..10).with_meta("doc");
+        assert_eq!(c.text, "ñ😀b\u{301}");
+        assert_eq!(c.char_len, c.text.chars().count());
+        assert_eq!((c.char_len, c.byte_len), (4, 9));
+    }
+
+    #[test]
+    fn serializable_chunk_to_json() {
+        let c = SerializableChunk {
+            doc_id: String::from("d\"1"),
+            index: 2,
+            chunk_id: format!("{:032x}", 0xabu128),
+            start: 3,
+            end: 9,
+            char_len: 5,
+            is_tail: true,
+            text: String::from("a\tb\n\u{1}é"),
+        };
+        assert_eq!(
+            c.to_json(),
+            r#"{"doc_id":"d\"1","index":2,"chunk_id":"000000000000000000000000000000ab","start":3,"end":9,"char_len":5,"is_tail":true,"text":"a\tb\n\u0001é"}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializable_chunk_round_trips_through_serde() {
+        let c = SerializableChunk {
+            doc_id: String::from("d\"1"),
+            index: 2,
+            chunk_id: format!("{:032x}", 0xabu128),
+            start: 3,
+            end: 9,
+            char_len: 5,
+            is_tail: true,
+            text: String::from("a\tb\n\u{1}é"),
+        };
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, c.to_json());
+        assert_eq!(serde_json::from_str::<SerializableChunk>(&json).unwrap(), c);
+
+        let bytes = bincode::serialize(&c).unwrap();
+        assert_eq!(bincode::deserialize::<SerializableChunk>(&bytes).unwrap(), c);
+    }
+
+    #[test]
+    fn chunk_id_matches_pinned_vectors() {
+        let vectors = include_str!("../../testdata/chunk_id_vectors.txt");
+        let mut checked = 0;
+        for line in vectors.lines().filter(|l| !l.starts_with('#')) {
+            let cols: Vec<_> = line.split('\t').collect();
+            let (doc, text) = (cols[0].replace("\\n", "\n"), cols[1].replace("\\n", "\n"));
+            let expected = u128::from_str_radix(cols[2], 16).unwrap();
+            assert_eq!(chunk_id(&doc, &text), expected, "{line}");
+            checked += 1;
+        }
+        assert_eq!(checked, 7);
+    }
+
+    #[test]
+    fn arc_chunk_derefs_into_shared_source() {
+        let source: Arc<str> = Arc::from("añbc");
+        let c = ArcChunk::new(source.clone(), 1..4);
+        assert_eq!(c, "ñb");
+        assert_eq!(c.len(), 3);
+        assert_eq!(c.range(), 1..4);
+        assert!(Arc::ptr_eq(c.source(), &source));
+        assert_eq!(format!("{c:?}"), r#"ArcChunk { text: "ñb", range: 1..4 }"#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn arc_chunk_serializes_as_its_text() {
+        let source: Arc<str> = Arc::from("añbc\"");
+        let chunks = vec![
+            ArcChunk::new(source.clone(), 1..4),
+            ArcChunk::new(source, 4..6),
+        ];
+        let json = serde_json::to_string(&chunks).unwrap();
+        assert_eq!(json, r#"["ñb","c\""]"#);
+        let back: Vec<ArcChunk> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, chunks);
+        assert_eq!(back[0].range(), 0..3);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a valid slice")]
+    fn arc_chunk_rejects_split_char() {
+        ArcChunk::new(Arc::from("añ"), 0..2);
+    }
+
+    #[test]
+    fn shared_slice_derefs_into_source() {
+        let source: Arc<[u32]> = Arc::from(vec![1, 2, 3, 4]);
+        let w = SharedSlice::new(source.clone(), 1..3);
+        assert_eq!(w, &[2, 3][..]);
+        assert_eq!(w.iter().sum::<u32>(), 5);
+        assert_eq!(w.clone().range(), 1..3);
+        assert!(Arc::ptr_eq(w.source(), &source));
+        assert_eq!(
+            format!("{w:?}"),
+            "SharedSlice { items: [2, 3], range: 1..3 }"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use std::ops::Range;
+
+use crate::splitter::from_char::Utf8Splitter;
+use crate::splitter::utils::{to_owned_all, SplitError, Tail};
+
+/// Returns the byte offsets at which a chunk may start: the start of every line that begins at
+/// curly-brace depth zero, plus the end of the input.
+///
+/// Braces inside string literals (including Rust raw strings), char literals, `//` line comments
+/// and (nested) `/* */` block comments are ignored. A `'` only opens a char literal when it looks
+/// like one (`'x'` or `'\..'`), so lifetimes don't derail the scan. This is a heuristic scanner
+/// for C-like languages, not a parser.
+pub fn depth_zero_breaks(input: &str) -> Vec<usize> {
+    let bytes = input.as_bytes();
+    let len = bytes.len();
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0usize;
+
+    while i < len {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = input[i..].find('\n').map_or(len, |e| i + e);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let mut nest = 1usize;
+                i += 2;
+                while i < len && nest > 0 {
+                    if bytes[i..].starts_with(b"/*") {
+                        nest += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        nest -= 1;
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            b'r' if raw_string_hashes(&bytes[i + 1..]).is_some()
+                && (i == 0 || !is_ident(bytes[i - 1])) =>
+            {
+                let hashes = raw_string_hashes(&bytes[i + 1..]).unwrap_or(0);
+                let open = i + 1 + hashes + 1;
+                let mut close = vec![b'"'];
+                close.extend(std::iter::repeat(b'#').take(hashes));
+                i = find_bytes(&bytes[open..], &close).map_or(len, |e| open + e + close.len());
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while i < len && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+                continue;
+            }
+            b'\'' => {
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    i = input[i + 2..].find('\'').map_or(len, |e| i + 2 + e + 1);
+                    continue;
+                }
+                if let Some(c) = input[i + 1..].chars().next() {
+                    let after = i + 1 + c.len_utf8();
+                    if bytes.get(after) == Some(&b'\'') {
+                        i = after + 1;
+                        continue;
+                    }
+                }
+            }
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b'\n' if depth == 0 => out.push(i + 1),
+            _ => {}
+        }
+        i += 1;
+    }
+    if out.last() != Some(&len) {
+        out.push(len);
+    }
+    out
+}
+
+/// If `rest` follows an `r` and starts a raw string (`#*"`), returns the number of `#`s.
+fn raw_string_hashes(rest: &[u8]) -> Option<usize> {
+    let hashes = rest.iter().take_while(|&&b| b == b'#').count();
+    (rest.get(hashes) == Some(&b'"')).then_some(hashes)
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// A splitter for source code that prefers breaking between top-level items.
+///
+/// - `input`: The source text.
+/// - `max_chars`: Maximum number of characters per chunk.
+/// - `overlap`: Characters shared between consecutive windows when a single oversized item is
+///   split with `Utf8Splitter`. Packed regions never overlap.
+///
+/// Regions between depth-zero line starts (see `depth_zero_breaks`) are packed greedily into
+/// chunks of at most `max_chars` characters.
+pub struct CodeSplitter<'a> {
+    input: &'a str,
+    max_chars: usize,
+    overlap: usize,
+}
+
+impl<'a> CodeSplitter<'a> {
+    pub fn new(input: &'a str, max_chars: usize, overlap: usize) -> Self {
+        Self {
+            input,
+            max_chars,
+            overlap,
+        }
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        let mut out = Vec::new();
+        self.for_each_chunk(|c| out.push(c));
+        out
+    }
+
+    /// Return the number of chunks, running the packing without collecting them.
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        self.for_each_chunk(|_| count += 1);
+        count
+    }
+
+    fn for_each_chunk(&self, mut emit: impl FnMut(&'a str)) {
+        let max = self.max_chars.max(1);
+        let step = max.saturating_sub(self.overlap).max(1);
+        let mut pending: Option<(Range<usize>, usize)> = None;
+        let mut start = 0usize;
+
+        for brk in depth_zero_breaks(self.input) {
+            if brk == start {
+                continue;
+            }
+            let region = start..brk;
+            start = brk;
+            let chars = self.input[region.clone()].chars().count();
+
+            if chars > max {
+                if let Some((range, _)) = pending.take() {
+                    emit(&self.input[range]);
+                }
+                let text = &self.input[region];
+                Utf8Splitter::with_tail(text, max, step, Tail::Keep)
+                    .split()
+                    .into_iter()
+                    .for_each(&mut emit);
+                continue;
+            }
+            pending = match pending.take() {
+                Some((range, n)) if n + chars <= max => Some((range.start..region.end, n + chars)),
+                Some((range, _)) => {
+                    emit(&self.input[range]);
+                    Some((region, chars))
+                }
+                None => Some((region, chars)),
+            };
+        }
+        if let Some((range, _)) = pending {
+            emit(&self.input[range]);
+        }
+    }
+
+    /// Like `split`, but rejects a zero `max_chars` or an `overlap` of at least `max_chars`
+    /// instead of clamping them.
+    pub fn try_split(&self) -> Result<Vec<&'a str>, SplitError> {
+        SplitError::check_overlap(self.max_chars, self.overlap)?;
+        Ok(self.split())
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r##"// top { comment
+fn a() {
+    let s = "}{ in a string";
+    let c = '}';
+    let r = r#"raw "}" string"#;
+    /* block { /* nested } */ still } comment */
+}
+
+struct B<'a> {
+    x: &'a str,
+}
+
+fn c() {}
+"##;
+
+    #[test]
+    fn breaks_only_between_items() {
+        let breaks = depth_zero_breaks(SRC);
+        let starts: Vec<_> = breaks
+            .iter()
+            .filter(|&&b| b < SRC.len())
+            .map(|&b| SRC[b..].lines().next().unwrap())
+            .collect();
+        assert_eq!(
+            starts,
+            vec!["fn a() {", "", "struct B<'a> {", "", "fn c() {}"]
+        );
+        assert_eq!(*breaks.last().unwrap(), SRC.len());
+    }
+
+    #[test]
+    fn packs_items_into_chunks() {
+        let chunks = CodeSplitter::new(SRC, 160, 0).split();
+        assert_eq!(chunks.concat(), SRC);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("// top") && chunks[0].ends_with("}\n\n"));
+        assert!(chunks[1].starts_with("struct B"));
+    }
+
+    #[test]
+    fn oversized_item_falls_back_to_char_windows() {
+        let src = "fn big() {\n    0123456789;\n}\nfn s() {}\n";
+        let chunks = CodeSplitter::new(src, 12, 0).split();
+        assert_eq!(chunks.concat(), src);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 12));
+        assert_eq!(*chunks.last().unwrap(), "fn s() {}\n");
+    }
+
+    #[test]
+    fn count_matches_split() {
+        for max in [5, 12, 40, 160] {
+            let sp = CodeSplitter::new(SRC, max, 2);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+}
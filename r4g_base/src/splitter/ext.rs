@@ -0,0 +1,100 @@
+use crate::splitter::from_char::char_window_ranges;
+use crate::splitter::utils::{window_count, Tail};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for str {}
+}
+
+/// Character-window splitting as methods on `str`, equivalent to `Utf8Splitter::new(..)` with
+/// default options.
+///
+/// The trait is sealed: it is implemented for `str` only and cannot be implemented outside this
+/// crate, so methods can be added without breaking anyone.
+///
+/// ```
+/// use r4g_base::prelude::*;
+///
+/// let text = "abcdefg";
+/// assert_eq!(text.char_windows(3, 2, false), vec!["abc", "cde", "efg"]);
+/// assert_eq!(text.char_windows(3, 0, true), vec!["abc", "def", "g"]);
+/// assert_eq!(text.char_window_count(3, 0, true), 3);
+/// ```
+pub trait StrSplitExt: sealed::Sealed {
+    /// Windows of `n` characters every `step` characters (`0` means `step = n`), keeping a
+    /// shorter final window if `keep_tail` is set. Same as `Utf8Splitter::new(..).split()`.
+    ///
+    /// ```
+    /// use r4g_base::prelude::*;
+    ///
+    /// assert_eq!("héllo wörld".char_windows(4, 0, true), vec!["héll", "o wö", "rld"]);
+    /// ```
+    fn char_windows(&self, n: usize, step: usize, keep_tail: bool) -> Vec<&str>;
+
+    /// Like `char_windows`, but yields the windows lazily, one character scan for all of them.
+    ///
+    /// ```
+    /// use r4g_base::prelude::*;
+    ///
+    /// let mut it = "a😀bcdef".char_windows_iter(2, 1, false);
+    /// assert_eq!(it.next(), Some("a😀"));
+    /// assert_eq!(it.next(), Some("😀b"));
+    /// assert_eq!(it.last(), Some("ef"));
+    /// ```
+    fn char_windows_iter(
+        &self,
+        n: usize,
+        step: usize,
+        keep_tail: bool,
+    ) -> impl Iterator<Item = &str> + '_;
+
+    /// The number of windows `char_windows` returns, counting characters without slicing.
+    ///
+    /// ```
+    /// use r4g_base::prelude::*;
+    ///
+    /// let text = "x".repeat(250);
+    /// assert_eq!(text.char_window_count(100, 80, true), 3);
+    /// assert_eq!(text.char_window_count(100, 80, true), text.char_windows(100, 80, true).len());
+    /// ```
+    fn char_window_count(&self, n: usize, step: usize, keep_tail: bool) -> usize;
+}
+
+impl StrSplitExt for str {
+    fn char_windows(&self, n: usize, step: usize, keep_tail: bool) -> Vec<&str> {
+        let mut out = Vec::with_capacity(self.char_window_count(n, step, keep_tail));
+        out.extend(self.char_windows_iter(n, step, keep_tail));
+        out
+    }
+
+    fn char_windows_iter(
+        &self,
+        n: usize,
+        step: usize,
+        keep_tail: bool,
+    ) -> impl Iterator<Item = &str> + '_ {
+        let num_chars = self.chars().count();
+        char_window_ranges(self, num_chars, n, step, Tail::from(keep_tail)).map(move |r| &self[r])
+    }
+
+    fn char_window_count(&self, n: usize, step: usize, keep_tail: bool) -> usize {
+        window_count(self.chars().count(), n, step, Tail::from(keep_tail))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::splitter::from_char::Utf8Splitter;
+
+    #[test]
+    fn matches_utf8_splitter() {
+        let s = "añ😀b\u{301}cdé fg";
+        for (n, step, keep) in [(3, 0, true), (3, 1, false), (4, 6, true), (20, 0, false)] {
+            let sp = Utf8Splitter::new(s, n, step, keep);
+            assert_eq!(s.char_windows(n, step, keep), sp.split());
+            assert_eq!(s.char_window_count(n, step, keep), sp.chunk_count());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::splitter::utils::Tail;
+
+/// Moves `pos` back to the start of the UTF-8 sequence it falls inside, or forward past the
+/// sequence when its start is at or before `floor` (so a window is never emptied).
+/// Positions in invalid data (more than three continuation bytes in a row) are left unchanged.
+fn snap_to_boundary(bytes: &[u8], pos: usize, floor: usize) -> usize {
+    let is_cont = |p: usize| bytes.get(p).is_some_and(|b| b & 0xC0 == 0x80);
+    if !is_cont(pos) {
+        return pos;
+    }
+    match (1..=3)
+        .filter_map(|k| pos.checked_sub(k))
+        .find(|&p| !is_cont(p))
+    {
+        Some(lead) if lead > floor => lead,
+        Some(_) => (pos + 1..=(pos + 3).min(bytes.len()))
+            .find(|&p| !is_cont(p))
+            .unwrap_or(pos),
+        None => pos,
+    }
+}
+
+/// Byte windows over `input`, snapped back to UTF-8 sequence starts where the data allows.
+///
+/// - `n`: The number of bytes per window.
+/// - `step`: Step between window starts (`0` means `step = n`).
+/// - `tail`: How to handle remaining bytes at the end (`bool` or `Tail`).
+///
+/// Snapping shortens a window, except that a single sequence longer than `n` is kept whole.
+/// The next window starts no later than the previous end unless `step > n`, so no bytes are
+/// dropped by the adjustment.
+pub fn bytes_windows(
+    input: &[u8],
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+) -> Vec<Range<usize>> {
+    let tail = tail.into();
+    let len = input.len();
+    let hop = if step == 0 { n } else { step };
+    let mut out = Vec::new();
+    if n == 0 {
+        return out;
+    }
+
+    let mut start = 0usize;
+    while start < len {
+        if start + n > len {
+            match tail {
+                Tail::Keep => out.push(start..len),
+                Tail::PadBackward => {
+                    let s = len.saturating_sub(n);
+                    out.push(snap_to_boundary(input, s, 0).min(s)..len);
+                }
+                Tail::Drop | Tail::Exact => {}
+            }
+            break;
+        }
+        let end = snap_to_boundary(input, start + n, start);
+        out.push(start..end);
+
+        let mut next = snap_to_boundary(input, start + hop, start);
+        if hop <= n {
+            next = next.min(end);
+        }
+        start = next;
+    }
+    out
+}
+
+/// A splitter for byte buffers that are mostly, but not necessarily entirely, valid UTF-8.
+///
+/// - `input`: The input bytes.
+/// - `n`: The number of bytes per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining bytes at the end (see `Tail`).
+///
+/// Each chunk is decoded with `String::from_utf8_lossy`: valid chunks are borrowed, invalid
+/// sequences are replaced with `U+FFFD` in an owned string.
+pub struct BytesSplitter<'a> {
+    input: &'a [u8],
+    n: usize,
+    step: usize,
+    tail: Tail,
+}
+
+impl<'a> BytesSplitter<'a> {
+    pub fn new(input: &'a [u8], n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(input, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(input: &'a [u8], n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            input,
+            n,
+            step,
+            tail,
+        }
+    }
+
+    pub fn split_lossy(&self) -> Vec<Cow<'a, str>> {
+        bytes_windows(self.input, self.n, self.step, self.tail)
+            .into_iter()
+            .map(|r| String::from_utf8_lossy(&self.input[r]))
+            .collect()
+    }
+
+    /// Return the number of chunks, computing the windows without decoding them.
+    pub fn count(&self) -> usize {
+        bytes_windows(self.input, self.n, self.step, self.tail).len()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        self.split_lossy()
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_utf8_is_borrowed_and_snapped() {
+        let s = "ab😀cd";
+        let v = BytesSplitter::new(s.as_bytes(), 4, 0, true).split_lossy();
+        assert_eq!(v, vec!["ab", "😀", "cd"]);
+        assert!(v.iter().all(|c| matches!(c, Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn invalid_bytes_are_replaced() {
+        let data = b"abc\xFFdef\xC3";
+        let v = BytesSplitter::new(data, 4, 0, true).split_lossy();
+        assert_eq!(
+            v,
+            vec![Cow::Borrowed("abc\u{FFFD}"), Cow::Borrowed("def\u{FFFD}")]
+        );
+        assert!(matches!(v[0], Cow::Owned(_)));
+    }
+
+    #[test]
+    fn windows_cover_input_with_overlap() {
+        let s = "é😀xyz";
+        let ranges = bytes_windows(s.as_bytes(), 5, 3, true);
+        let mut covered = 0;
+        for r in &ranges {
+            assert!(s.is_char_boundary(r.start) && s.is_char_boundary(r.end));
+            assert!(r.start <= covered);
+            covered = covered.max(r.end);
+        }
+        assert_eq!(covered, s.len());
+    }
+
+    #[test]
+    fn count_matches_split() {
+        let data = "é😀xyz\u{FFFD}ab".as_bytes();
+        for n in 1..8 {
+            let sp = BytesSplitter::new(data, n, 2, true);
+            assert_eq!(sp.count(), sp.split_lossy().len());
+        }
+    }
+}
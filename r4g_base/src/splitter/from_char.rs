@@ -1,42 +1,544 @@
-use crate::splitter::utils::sliding_windows;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hasher;
+use std::iter::Peekable;
+use std::ops::{ControlFlow, Range};
+use std::str::CharIndices;
+use std::sync::Arc;
+
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::splitter::chunk::{chunk_id, ArcChunk, Chunk, SerializableChunk};
+use crate::splitter::preprocess::{PreProcessor, Preprocessed};
+#[cfg(not(feature = "unicode"))]
+use crate::splitter::utils::is_cluster_extend;
+use crate::splitter::utils::{
+    back_chars, forward_chars, hash_chunk, nth_window, par_map_ordered, par_map_runs,
+    sliding_windows, split_into_k, to_owned_all, window_count, SplitError, SplitWarning,
+    StableHasher, Tail, TrimPolicy,
+};
+
+/// Computes the byte ranges of windows of `n` characters each, optionally overlapping, with optional tail.
+///
+/// Takes the same parameters as `utf8_by_chars_borrowed`; every range lies on char boundaries.
+pub fn utf8_by_chars_indices(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+) -> Vec<Range<usize>> {
+    windows_over_units(input, &char_offsets(input), n, step, tail, false)
+}
+
+/// The byte offset at which each character of `input` starts. Capacity is reserved for the
+/// all-ASCII worst case of one character per byte, so the vector never reallocates.
+fn char_offsets(input: &str) -> Vec<usize> {
+    let mut starts = Vec::with_capacity(input.len());
+    starts.extend(input.char_indices().map(|(i, _)| i));
+    starts
+}
+
+/// Maps increasing character positions to byte offsets in a single forward pass.
+struct CharCursor<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    pos: usize,
+}
+
+impl<'a> CharCursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            pos: 0,
+        }
+    }
+
+    /// Byte offset of character `c` (`input.len()` past the end). `c` must not decrease between
+    /// calls.
+    fn byte_at(&mut self, c: usize) -> usize {
+        while self.pos < c && self.chars.next().is_some() {
+            self.pos += 1;
+        }
+        self.chars.peek().map_or(self.input.len(), |&(b, _)| b)
+    }
+}
+
+/// Byte ranges of the windows of `n` characters every `step` characters over `input`, which has
+/// `num_chars` characters, computed lazily with two forward cursors.
+pub(crate) fn char_window_ranges(
+    input: &str,
+    num_chars: usize,
+    n: usize,
+    step: usize,
+    tail: Tail,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let (mut starts, mut ends) = (CharCursor::new(input), CharCursor::new(input));
+    sliding_windows(num_chars, n, step, tail)
+        .map(move |r| starts.byte_at(r.start)..ends.byte_at(r.end))
+}
+
+/// The windows of `char_window_ranges`, each with the `hash_chunk` of its text. The characters
+/// are walked once and each one is fed to the hasher of every window still open over it, so no
+/// window is read again once its boundaries are known.
+fn hashed_char_windows(
+    input: &str,
+    num_chars: usize,
+    n: usize,
+    step: usize,
+    tail: Tail,
+) -> Vec<(Range<usize>, u64)> {
+    let mut windows = sliding_windows(num_chars, n, step, tail).peekable();
+    // Window starts increase and ends never decrease, so windows close in the order they open.
+    let mut open: VecDeque<(usize, usize, StableHasher)> = VecDeque::new();
+    let mut out = Vec::with_capacity(window_count(num_chars, n, step, tail));
+    let chars = input.char_indices().map(Some).chain([None]);
+    for (k, item) in chars.enumerate() {
+        let byte = item.map_or(input.len(), |(b, _)| b);
+        while let Some(w) = windows.next_if(|w| w.start <= k) {
+            open.push_back((w.end, byte, StableHasher::default()));
+        }
+        while let Some(&(end, start, hasher)) = open.front() {
+            if end > k {
+                break;
+            }
+            open.pop_front();
+            out.push((start..byte, hasher.finish()));
+        }
+        if let Some((b, c)) = item {
+            let bytes = &input.as_bytes()[b..b + c.len_utf8()];
+            for (_, _, hasher) in &mut open {
+                hasher.write(bytes);
+            }
+        }
+    }
+    out
+}
+
+/// Byte offsets of the counting units of `input` with line endings normalized: a leading BOM is
+/// not counted (the first unit starts after it) and `\r\n` counts as one unit.
+fn line_normalized_units(input: &str) -> Vec<usize> {
+    let body = input.strip_prefix('\u{FEFF}').unwrap_or(input);
+    let offset = input.len() - body.len();
+    body.char_indices()
+        .filter(|&(i, c)| !(c == '\n' && i > 0 && body.as_bytes()[i - 1] == b'\r'))
+        .map(|(i, _)| offset + i)
+        .collect()
+}
+
+/// Byte length of the ANSI escape sequence at the start of `s`, which starts with ESC: a CSI
+/// sequence (`ESC [`, parameter and intermediate bytes, then a final byte in `@`..=`~`), an OSC
+/// sequence (`ESC ]` up to BEL or `ESC \`), or ESC and one more character. An unterminated
+/// sequence runs to the end of `s`; a CSI sequence interrupted by any other byte ends before it.
+fn ansi_escape_len(s: &str) -> usize {
+    let b = s.as_bytes();
+    match b.get(1) {
+        Some(b'[') => match b[2..].iter().position(|c| !(0x20..=0x3F).contains(c)) {
+            Some(p) if (0x40..=0x7E).contains(&b[2 + p]) => p + 3,
+            Some(p) => p + 2,
+            None => b.len(),
+        },
+        Some(b']') => (2..b.len())
+            .find_map(|i| match b[i] {
+                0x07 => Some(i + 1),
+                0x1B if b.get(i + 1) == Some(&b'\\') => Some(i + 2),
+                _ => None,
+            })
+            .unwrap_or(b.len()),
+        Some(_) => 1 + s[1..].chars().next().map_or(0, char::len_utf8),
+        None => 1,
+    }
+}
+
+/// `units` without those inside ANSI escape sequences, so each sequence is counted as part of
+/// the unit before it. Sequences before the first visible unit join that unit instead.
+fn ansi_visible_units(input: &str, units: &[usize]) -> Vec<usize> {
+    let mut hidden = Vec::new();
+    let mut at = 0;
+    while let Some(p) = input[at..].find('\u{1b}') {
+        let start = at + p;
+        at = start + ansi_escape_len(&input[start..]);
+        hidden.push(start..at);
+    }
+    let mut next_hidden = hidden.iter().peekable();
+    let mut kept: Vec<usize> = units
+        .iter()
+        .copied()
+        .filter(|&u| {
+            while next_hidden.next_if(|h| h.end <= u).is_some() {}
+            !next_hidden.peek().is_some_and(|h| h.contains(&u))
+        })
+        .collect();
+    if let (Some(first), Some(&unit)) = (kept.first_mut(), units.first()) {
+        *first = unit;
+    }
+    kept
+}
+
+/// Whether a window may start or end at each of `units` without splitting a grapheme cluster:
+/// whether the offset is an extended grapheme cluster boundary (UAX #29), as computed by
+/// `unicode-segmentation`.
+#[cfg(feature = "unicode")]
+fn cluster_safe_units(input: &str, units: &[usize]) -> Vec<bool> {
+    let mut bounds = input.grapheme_indices(true).map(|(i, _)| i).peekable();
+    units
+        .iter()
+        .map(|&u| {
+            while bounds.next_if(|&b| b < u).is_some() {}
+            bounds.peek() == Some(&u)
+        })
+        .collect()
+}
+
+/// Without the `unicode` feature, an approximation of `cluster_safe_units`: a unit is unsafe if it
+/// starts with an `is_cluster_extend` character or follows a ZWJ. Regional indicator pairs (flags)
+/// and Hangul syllable sequences are not kept together.
+#[cfg(not(feature = "unicode"))]
+fn cluster_safe_units(input: &str, units: &[usize]) -> Vec<bool> {
+    units
+        .iter()
+        .map(|&u| {
+            let first = input[u..].chars().next();
+            let after_zwj = input[..u].ends_with('\u{200D}');
+            !first.is_some_and(is_cluster_extend) && !after_zwj
+        })
+        .collect()
+}
+
+/// Windows over `units`, the sorted byte offsets at which each counting unit starts; a unit ends
+/// where the next begins, the last at the end of `input`.
+///
+/// With `keep_clusters`, every window start and end that would split a grapheme cluster (see
+/// `cluster_safe_units`) is nudged forward to the next safe unit. Starts and ends move
+/// identically, so nothing is duplicated or lost; windows that become empty are dropped.
+fn windows_over_units(
+    input: &str,
+    units: &[usize],
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+    keep_clusters: bool,
+) -> Vec<Range<usize>> {
+    let num_units = units.len();
+    let safe: Vec<bool> = if keep_clusters {
+        cluster_safe_units(input, units)
+    } else {
+        Vec::new()
+    };
+    let nudge = |i: usize| {
+        if safe.is_empty() {
+            return i;
+        }
+        (i..num_units).find(|&j| safe[j]).unwrap_or(num_units)
+    };
+
+    let tail = tail.into();
+    let mut out = Vec::with_capacity(window_count(num_units, n, step, tail));
+    for r in sliding_windows(num_units, n, step, tail) {
+        let (start, end) = (nudge(r.start), nudge(r.end));
+        if keep_clusters && start >= end && !r.is_empty() {
+            continue;
+        }
+        let byte_start = if start < num_units {
+            units[start]
+        } else {
+            input.len()
+        };
+        let byte_end = if end < num_units {
+            units[end]
+        } else {
+            input.len()
+        };
+        out.push(byte_start..byte_end);
+    }
+    out
+}
 
 /// Splits a UTF-8 string into borrowed substrings of `n` characters each, optionally overlapping, with optional tail.
 ///
 /// - `input`: The input string.
 /// - `n`: The number of characters per substring.
 /// - `step`: Step between window starts (`0` means `step = n`).
-/// - `keep_tail`: If true, includes a final substring for remaining characters at the end.
+/// - `tail`: How to handle remaining characters at the end (`bool` or `Tail`).
 ///
 /// Returns a vector of string slices (`&str`) borrowing from the input.
 pub fn utf8_by_chars_borrowed(
     input: &str,
     n: usize,
     step: usize,
-    keep_tail: bool,
+    tail: impl Into<Tail>,
 ) -> Vec<&str> {
-    let char_indices: Vec<usize> = input.char_indices().map(|(i, _)| i).collect();
-    let num_chars = char_indices.len();
+    utf8_by_chars_indices(input, n, step, tail)
+        .into_iter()
+        .map(|r| &input[r])
+        .collect()
+}
+
+/// Splits `text` into chunks of `size` characters, each sharing `overlap` characters with the
+/// one before, keeping a shorter last chunk: `Utf8Splitter::new(text, size, size - overlap,
+/// true).split()` in one call.
+///
+/// ```
+/// let chunks = r4g_base::chunk("the quick brown fox", 8, 2);
+/// assert_eq!(chunks, vec!["the quic", "ick brow", "own fox"]);
+/// ```
+///
+/// # Panics
+///
+/// If `size` is `0` or `overlap` is not less than `size`; use `Utf8Splitter::try_split` to
+/// handle computed parameters without panicking.
+pub fn chunk(text: &str, size: usize, overlap: usize) -> Vec<&str> {
+    if let Err(e) = SplitError::check_overlap(size, overlap) {
+        panic!("invalid chunk parameters: {e}");
+    }
+    Utf8Splitter::new(text, size, size - overlap, true).split()
+}
+
+/// Splits each of `docs` with the settings of `config`, in parallel with the `rayon` feature (one
+/// document per task) and sequentially without it. Each document's chunks borrow from that
+/// document, and the results are in the order of `docs`.
+///
+/// ```
+/// use r4g_base::splitter::{split_many, SplitterConfig};
+///
+/// let config = SplitterConfig::new(4, 0, true);
+/// let chunks = split_many(&["abcdef", "ghij"], &config);
+/// assert_eq!(chunks, vec![vec!["abcd", "ef"], vec!["ghij"]]);
+/// ```
+pub fn split_many<'a>(docs: &[&'a str], config: &SplitterConfig) -> Vec<Vec<&'a str>> {
+    par_map_runs(docs, 1, |doc| config.splitter(doc).split())
+}
+
+/// Like `utf8_by_chars_borrowed`, but clears and refills `out` instead of allocating a new vector.
+pub fn utf8_by_chars_borrowed_into<'a>(
+    input: &'a str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+    out: &mut Vec<&'a str>,
+) {
+    Utf8Splitter::with_tail(input, n, step, tail.into()).split_into(out);
+}
+
+/// Splits a UTF-8 string into borrowed substrings of `n` UTF-16 code units each, optionally
+/// overlapping, with optional tail. Astral characters (outside the BMP) count as 2 units,
+/// matching JavaScript and Windows string lengths.
+///
+/// - `input`: The input string.
+/// - `n`: The number of UTF-16 code units per substring.
+/// - `step`: Step between window starts in code units (`0` means `step = n`).
+/// - `tail`: How to handle remaining characters at the end (`bool` or `Tail`).
+///
+/// Boundaries snap down to char boundaries, so a surrogate pair is never split and a chunk may be
+/// one unit under `n` when an astral character straddles its end. A single astral character is
+/// never dropped: with `n == 1` it is returned as a 2-unit chunk.
+pub fn utf16_by_units_borrowed(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+) -> Vec<&str> {
+    let tail = tail.into();
+    let hop = if step == 0 { n } else { step };
+    // Byte and UTF-16 offsets of every char boundary, including the end of the input.
+    let mut bytes = Vec::new();
+    let mut units = Vec::new();
+    let mut u = 0usize;
+    for (b, c) in input.char_indices() {
+        bytes.push(b);
+        units.push(u);
+        u += c.len_utf16();
+    }
+    bytes.push(input.len());
+    units.push(u);
+    let total = u;
+    let last = units.len() - 1;
+    // Last char boundary at or before unit offset `target`.
+    let floor = |target: usize| units.partition_point(|&x| x <= target) - 1;
 
     let mut out = Vec::new();
-    for r in sliding_windows(num_chars, n, step, keep_tail) {
-        let byte_start = char_indices[r.start];
-        let byte_end = if r.end < num_chars {
-            char_indices[r.end]
-        } else {
-            input.len()
-        };
-        out.push(&input[byte_start..byte_end]);
+    if n == 0 {
+        return out;
+    }
+    let mut pos = 0usize;
+    // Unit offset where the last full window ends.
+    let mut covered = 0usize;
+    while pos < last {
+        let u0 = units[pos];
+        if u0 + n > total {
+            let start = match tail {
+                Tail::Keep => Some(pos),
+                Tail::PadBackward if covered < total => {
+                    Some(units.partition_point(|&x| x < total.saturating_sub(n)))
+                }
+                Tail::PadBackward | Tail::Drop | Tail::Exact => None,
+            };
+            if let Some(start) = start {
+                out.push(&input[bytes[start]..input.len()]);
+            }
+            break;
+        }
+        let end = floor(u0 + n).max(pos + 1);
+        out.push(&input[bytes[pos]..bytes[end]]);
+        covered = units[end];
+        pos = floor(u0 + hop).max(pos + 1);
     }
     out
 }
 
 /// Converts a vector of string slices into a vector of owned strings.
+///
+/// A string-only shorthand for `to_owned_all`.
 pub fn bulk_to_owned_into(windows: Vec<&str>) -> Vec<String> {
-    let mut out = Vec::with_capacity(windows.len());
-    for s in windows {
-        out.push(s.to_string());
+    to_owned_all(windows)
+}
+
+/// Summary of the chunks a `Utf8Splitter` produces, from `Utf8Splitter::stats`. Lengths are in
+/// characters; all fields are `0` when there are no chunks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitStats {
+    /// Number of chunks.
+    pub count: usize,
+    /// Length of the shortest chunk.
+    pub min_chars: usize,
+    /// Length of the longest chunk.
+    pub max_chars: usize,
+    /// Mean chunk length.
+    pub mean_chars: f64,
+    /// Characters shared by consecutive chunks, summed over all pairs (see `overlaps`).
+    pub total_overlap_chars: usize,
+}
+
+/// A string with its char-to-byte index computed once, for splitting the same text repeatedly
+/// (e.g. sweeping `n`/`step` while tuning chunk parameters).
+pub struct IndexedStr<'a> {
+    input: &'a str,
+    char_indices: Vec<usize>,
+}
+
+impl<'a> IndexedStr<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            char_indices: char_offsets(input),
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.input
+    }
+
+    /// The byte offset at which each character starts.
+    pub fn char_indices(&self) -> &[usize] {
+        &self.char_indices
+    }
+
+    pub fn num_chars(&self) -> usize {
+        self.char_indices.len()
+    }
+
+    /// Same as `utf8_by_chars_borrowed` on the underlying string, without rescanning it.
+    pub fn windows(&self, n: usize, step: usize, tail: impl Into<Tail>) -> Vec<&'a str> {
+        windows_over_units(self.input, &self.char_indices, n, step, tail, false)
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+}
+
+/// The settings of a `Utf8Splitter` without its input, to split many inputs the same way (see
+/// `split_many`).
+///
+/// - `n`: The number of characters per substring.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// The builder methods are those of `Utf8Splitter`, with the same defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitterConfig {
+    n: usize,
+    step: usize,
+    tail: Tail,
+    trim: TrimPolicy,
+    skip_empty: bool,
+    skip_blank: bool,
+    normalize_line_endings: bool,
+    keep_clusters: bool,
+    ansi_aware: bool,
+}
+
+impl SplitterConfig {
+    pub fn new(n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            n,
+            step,
+            tail,
+            trim: TrimPolicy::None,
+            skip_empty: false,
+            skip_blank: false,
+            normalize_line_endings: false,
+            keep_clusters: false,
+            ansi_aware: false,
+        }
+    }
+
+    /// A splitter over `input` with these settings.
+    pub fn splitter<'a>(&self, input: &'a str) -> Utf8Splitter<'a> {
+        Utf8Splitter {
+            input,
+            char_indices: None,
+            n: self.n,
+            step: self.step,
+            tail: self.tail,
+            trim: self.trim,
+            skip_empty: self.skip_empty,
+            skip_blank: self.skip_blank,
+            normalize_line_endings: self.normalize_line_endings,
+            keep_clusters: self.keep_clusters,
+            ansi_aware: self.ansi_aware,
+        }
+    }
+
+    /// See `Utf8Splitter::trim`.
+    pub fn trim(mut self, trim: TrimPolicy) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// See `Utf8Splitter::skip_empty`.
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// See `Utf8Splitter::skip_blank`.
+    pub fn skip_blank(mut self, skip: bool) -> Self {
+        self.skip_blank = skip;
+        self
+    }
+
+    /// See `Utf8Splitter::normalize_line_endings`.
+    pub fn normalize_line_endings(mut self, normalize: bool) -> Self {
+        self.normalize_line_endings = normalize;
+        self
+    }
+
+    /// See `Utf8Splitter::avoid_breaking_clusters`.
+    pub fn avoid_breaking_clusters(mut self, avoid: bool) -> Self {
+        self.keep_clusters = avoid;
+        self
+    }
+
+    /// See `Utf8Splitter::ansi_aware`.
+    pub fn ansi_aware(mut self, aware: bool) -> Self {
+        self.ansi_aware = aware;
+        self
     }
-    out
 }
 
 
@@ -45,33 +547,993 @@ pub fn bulk_to_owned_into(windows: Vec<&str>) -> Vec<String> {
 /// - `input`: The input string slice to split.
 /// - `n`: The number of characters per substring.
 /// - `step`: Step between window starts (`0` means no overlap).
-/// - `keep_tail`: If true, includes a final substring for any remaining characters at the end.
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
 ///
-/// Provides methods to get borrowed string slices (`split`) or owned `String`s (`out`).
+/// Provides methods to get borrowed string slices (`split`), their byte ranges (`split_indices`)
+/// or owned `String`s (`out`). Windows can be trimmed of whitespace (`trim`), chunks left empty
+/// by trimming dropped (`skip_empty`), and whitespace-only chunks skipped (`skip_blank`); all of
+/// these work on the borrowed ranges rather than allocating.
+///
+/// Built with `from_indexed`, the splitter reuses the precomputed char index of an `IndexedStr`
+/// instead of scanning the input on every call.
+///
+/// An `n` of `0` is not an error for `split`: it yields an empty chunk at every `step`-th
+/// character boundary, end of input included, as `sliding_windows` does; with `step` also `0`
+/// it yields nothing. `try_split` rejects both instead.
 pub struct Utf8Splitter<'a> {
     input: &'a str,
+    char_indices: Option<&'a [usize]>,
     n: usize,
     step: usize,
-    keep_tail: bool,
+    tail: Tail,
+    trim: TrimPolicy,
+    skip_empty: bool,
+    skip_blank: bool,
+    normalize_line_endings: bool,
+    keep_clusters: bool,
+    ansi_aware: bool,
 }
 
 impl<'a> Utf8Splitter<'a> {
     pub fn new(input: &'a str, n: usize, step: usize, keep_tail: bool) -> Self {
-        Self { input, n, step, keep_tail }
+        Self::with_tail(input, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(input: &'a str, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            input,
+            char_indices: None,
+            n,
+            step,
+            tail,
+            trim: TrimPolicy::None,
+            skip_empty: false,
+            skip_blank: false,
+            normalize_line_endings: false,
+            keep_clusters: false,
+            ansi_aware: false,
+        }
+    }
+
+    /// Like `new`, also returning warnings about settings that are valid but often a mistake,
+    /// such as a `step` of `0` (no overlap, not full overlap) or a `step` larger than `n` (which
+    /// skips characters); see `SplitWarning`. The splitter is the same as from `new`.
+    pub fn checked_new(
+        input: &'a str,
+        n: usize,
+        step: usize,
+        keep_tail: bool,
+    ) -> (Self, Vec<SplitWarning>) {
+        let warnings = SplitWarning::check(input.chars().count(), n, step, keep_tail.into());
+        (Self::new(input, n, step, keep_tail), warnings)
+    }
+
+    /// Build a splitter over an already indexed string.
+    pub fn from_indexed(indexed: &'a IndexedStr<'a>, n: usize, step: usize, tail: Tail) -> Self {
+        let mut splitter = Self::with_tail(indexed.input, n, step, tail);
+        splitter.char_indices = Some(&indexed.char_indices);
+        splitter
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn tail(&self) -> Tail {
+        self.tail
+    }
+
+    /// The byte offset at which each character starts, borrowed from an `IndexedStr` if available.
+    fn char_starts(&self) -> Cow<'a, [usize]> {
+        match self.char_indices {
+            Some(indices) => Cow::Borrowed(indices),
+            None => Cow::Owned(char_offsets(self.input)),
+        }
+    }
+
+    /// The settings of this splitter, to apply to other inputs.
+    pub fn config(&self) -> SplitterConfig {
+        SplitterConfig {
+            n: self.n,
+            step: self.step,
+            tail: self.tail,
+            trim: self.trim,
+            skip_empty: self.skip_empty,
+            skip_blank: self.skip_blank,
+            normalize_line_endings: self.normalize_line_endings,
+            keep_clusters: self.keep_clusters,
+            ansi_aware: self.ansi_aware,
+        }
+    }
+
+    /// Trim whitespace from each window. Windows are computed first, then trimmed.
+    pub fn trim(mut self, trim: TrimPolicy) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Drop chunks that are empty (after trimming, if enabled).
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// Drop chunks that are empty or contain only whitespace. Unlike `skip_empty`, this does not
+    /// require trimming and leaves the kept chunks untouched.
+    pub fn skip_blank(mut self, skip: bool) -> Self {
+        self.skip_blank = skip;
+        self
+    }
+
+    /// Count `\r\n` as a single character and skip a leading BOM, so a document has the same chunk
+    /// boundaries whether it was saved with Windows or Unix line endings. The returned slices still
+    /// borrow the original bytes, `\r` included; only the BOM is left out of every chunk.
+    pub fn normalize_line_endings(mut self, normalize: bool) -> Self {
+        self.normalize_line_endings = normalize;
+        self
+    }
+
+    /// Never end a window inside a grapheme cluster. Such boundaries are nudged forward to the next
+    /// cluster boundary, for both the window end and the following window start, so a window may
+    /// run a few characters over `n`.
+    ///
+    /// With the `unicode` feature the clusters are the extended grapheme clusters of UAX #29, from
+    /// `unicode-segmentation`. Without it they are approximated: a character keeps any following
+    /// combining marks, ZWJ, variation selectors and skin-tone modifiers, and the character after
+    /// a ZWJ; flags and Hangul jamo sequences may still be split.
+    pub fn avoid_breaking_clusters(mut self, avoid: bool) -> Self {
+        self.keep_clusters = avoid;
+        self
+    }
+
+    /// Do not count ANSI escape sequences (CSI such as `\x1b[31m`, and OSC) as characters, and
+    /// never end a window inside one: a boundary that would fall in a sequence moves past its
+    /// terminator. Chunks still borrow the input verbatim, escapes included, so a chunk may run
+    /// past `n` characters by the sequences it carries. An unterminated sequence at the end of
+    /// the input belongs to the last chunk, and an input made only of escapes has no chunks.
+    /// `nth` and `chunks_containing` ignore this option (they follow the raw window sequence).
+    pub fn ansi_aware(mut self, aware: bool) -> Self {
+        self.ansi_aware = aware;
+        self
+    }
+
+    /// Run `steps` over `raw` and return the processed text, which owns the `String` the splitter
+    /// then borrows from: `let doc = Utf8Splitter::preprocessed(raw, &steps);` followed by
+    /// `doc.splitter(100, 20, true).split()`.
+    ///
+    /// Chunk offsets refer to the processed text; `Preprocessed::original_range` maps them back.
+    pub fn preprocessed(raw: &str, steps: &PreProcessor) -> Preprocessed {
+        steps.apply(raw)
+    }
+
+    /// Return the byte range of each chunk, reflecting `trim`, `skip_empty` and `skip_blank`.
+    ///
+    /// Skipped chunks are filtered out as they are generated, so chunk ordinals (positions in the
+    /// returned vector) are dense: they count kept chunks only and no gaps are left.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        let units = if self.normalize_line_endings {
+            Cow::Owned(line_normalized_units(self.input))
+        } else {
+            self.char_starts()
+        };
+        let units = if self.ansi_aware {
+            Cow::Owned(ansi_visible_units(self.input, &units))
+        } else {
+            units
+        };
+        let ranges = windows_over_units(
+            self.input,
+            &units,
+            self.n,
+            self.step,
+            self.tail,
+            self.keep_clusters,
+        );
+        ranges
+            .into_iter()
+            .map(|r| self.trim.apply(self.input, r))
+            .filter(|r| !(self.skip_empty && r.is_empty()))
+            .filter(|r| !(self.skip_blank && self.input[r.clone()].trim().is_empty()))
+            .collect()
     }
 
     pub fn split(&self) -> Vec<&'a str> {
-        utf8_by_chars_borrowed(self.input, self.n, self.step, self.keep_tail)
+        self.collect_chunks()
+    }
+
+    /// Collect the chunks into any `FromIterator` target, e.g. a `HashSet<&str>`, without an
+    /// intermediate vector of chunks.
+    pub fn collect_chunks<C: FromIterator<&'a str>>(&self) -> C {
+        self.chunk_iter().collect()
+    }
+
+    /// Like `collect_chunks`, with each chunk copied into an owned `String`.
+    pub fn collect_owned<C: FromIterator<String>>(&self) -> C {
+        self.chunk_iter().map(str::to_owned).collect()
+    }
+
+    /// The chunks of `split`, produced lazily: sliced from the cached char starts of
+    /// `from_indexed`, or by `char_window_ranges` otherwise. With `normalize_line_endings`,
+    /// `avoid_breaking_clusters` or `ansi_aware`, the boundaries come from `split_indices`
+    /// instead.
+    fn chunk_iter(&self) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        let input = self.input;
+        if self.normalize_line_endings || self.keep_clusters || self.ansi_aware {
+            return Box::new(self.split_indices().into_iter().map(move |r| &input[r]));
+        }
+        let windows: Box<dyn Iterator<Item = Range<usize>> + 'a> = match self.char_indices {
+            Some(starts) => {
+                let byte = move |c: usize| starts.get(c).copied().unwrap_or(input.len());
+                Box::new(
+                    sliding_windows(starts.len(), self.n, self.step, self.tail)
+                        .map(move |r| byte(r.start)..byte(r.end)),
+                )
+            }
+            None => {
+                let num_chars = input.chars().count();
+                Box::new(char_window_ranges(
+                    input, num_chars, self.n, self.step, self.tail,
+                ))
+            }
+        };
+        let (trim, skip_empty, skip_blank) = (self.trim, self.skip_empty, self.skip_blank);
+        Box::new(windows.filter_map(move |bytes| {
+            let text = &input[trim.apply(input, bytes)];
+            let skip = (skip_empty && text.is_empty()) || (skip_blank && text.trim().is_empty());
+            (!skip).then_some(text)
+        }))
+    }
+
+    /// Return the chunks for which `keep` is true, each paired with its index among the chunks of
+    /// `split`, so gaps in the indices show which chunks were dropped.
+    pub fn split_filtered(&self, keep: impl Fn(&str) -> bool) -> Vec<(usize, &'a str)> {
+        self.chunk_iter()
+            .enumerate()
+            .filter(|&(_, c)| keep(c))
+            .collect()
+    }
+
+    /// Like `split`, but clears and refills `out`, so a buffer reused across calls stops
+    /// allocating once it is large enough. Boundaries come from `for_each_chunk`.
+    pub fn split_into(&self, out: &mut Vec<&'a str>) {
+        out.clear();
+        out.reserve(self.chunk_count());
+        self.for_each_chunk(|_, c| {
+            out.push(c);
+            ControlFlow::Continue(())
+        });
     }
 
     pub fn out(&self) -> Vec<String> {
-        bulk_to_owned_into(self.split())
+        self.collect_owned()
+    }
+
+    /// Like `out`, with the chunks copied in parallel on the rayon pool (with the `rayon` feature;
+    /// sequentially otherwise). The byte ranges are computed first (see `split_indices`) and the
+    /// order matches `out`. Worth it for very large inputs only.
+    pub fn par_out(&self) -> Vec<String> {
+        let input = self.input;
+        par_map_ordered(&self.split_indices(), |r| input[r.clone()].to_string())
+    }
+
+    /// Like `out`, but every chunk starts as `Cow::Borrowed`, so only the chunks a caller
+    /// modifies (through `Cow::to_mut`) are copied.
+    pub fn out_cow(&self) -> Vec<Cow<'a, str>> {
+        self.chunk_iter().map(Cow::Borrowed).collect()
+    }
+
+    /// Call `f(index, chunk)` for each chunk as it is produced, stopping as soon as `f` returns
+    /// `ControlFlow::Break`; nothing after that, tail included, is visited.
+    ///
+    /// Window boundaries are computed on the fly from a character count and two forward cursors
+    /// (or the cached char starts of `from_indexed`), so no chunk or index vector is built.
+    /// `trim`, `skip_empty` and `skip_blank` apply as in `split`, and `index` counts the chunks
+    /// passed to `f`. With `normalize_line_endings`, `avoid_breaking_clusters` or `ansi_aware`,
+    /// the boundaries come from `split_indices` instead.
+    pub fn for_each_chunk(&self, mut f: impl FnMut(usize, &'a str) -> ControlFlow<()>) {
+        for (k, text) in self.chunk_iter().enumerate() {
+            if f(k, text).is_break() {
+                return;
+            }
+        }
+    }
+
+    /// Apply `f` to each chunk lazily, yielding the results in order without collecting the chunks.
+    ///
+    /// Window boundaries come from the same character cursors as `for_each_chunk`, so each chunk
+    /// is found, sliced and passed to `f` only as the iterator is advanced, and `take` stops the
+    /// scan early. With `normalize_line_endings`, `avoid_breaking_clusters` or `ansi_aware`, the
+    /// boundaries come from `split_indices` instead.
+    pub fn map<B, F>(self, f: F) -> impl Iterator<Item = B> + 'a
+    where
+        F: Fn(&'a str) -> B + 'a,
+    {
+        self.chunk_iter().map(f)
+    }
+
+    /// Like `split`, but rejects a zero window size (and a zero size with a zero step) instead of
+    /// returning empty chunks or never finishing.
+    pub fn try_split(&self) -> Result<Vec<&'a str>, SplitError> {
+        SplitError::check_window(self.n, self.step)?;
+        Ok(self.split())
+    }
+
+    /// Like `out`, with the checks of `try_split`.
+    pub fn try_out(&self) -> Result<Vec<String>, SplitError> {
+        self.try_split().map(bulk_to_owned_into)
+    }
+
+    /// Like `split`, but with leading and trailing whitespace trimmed from each chunk; chunks that
+    /// are empty after trimming are dropped.
+    pub fn split_trimmed(&self) -> Vec<&'a str> {
+        self.split_with_spans_trimmed()
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect()
+    }
+
+    /// Like `split_trimmed`, but each trimmed chunk is paired with the byte range of the window it
+    /// was trimmed from, so citations can still point at the untrimmed text.
+    pub fn split_with_spans_trimmed(&self) -> Vec<(&'a str, Range<usize>)> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| (self.input[r.clone()].trim(), r))
+            .filter(|(text, _)| !text.is_empty())
+            .collect()
+    }
+
+    /// Return each chunk with its byte range (see `split_indices`).
+    pub fn chunks(&self) -> Vec<Chunk<'a>> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| Chunk::new(self.input, r))
+            .collect()
+    }
+
+    /// Like `chunks`, with a clone of `meta` attached to every chunk, so the output can go
+    /// straight into a vector store insert.
+    ///
+    /// For heavy metadata pass an `Arc<M>`: each chunk then clones a pointer, not the metadata.
+    pub fn split_with_meta<M: Clone>(&self, meta: M) -> Vec<Chunk<'a, M>> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| Chunk::new(self.input, r).with_meta(meta.clone()))
+            .collect()
+    }
+
+    /// Same as `chunks`; see `reconstruct` to stitch the spans back together.
+    pub fn split_with_spans(&self) -> Vec<Chunk<'a>> {
+        self.chunks()
+    }
+
+    /// Return `(chunk_id(doc_id, chunk), byte_range, chunk)` for each chunk, so unchanged chunks of
+    /// a re-ingested document keep their ids.
+    pub fn split_with_ids(&self, doc_id: &str) -> Vec<(u128, Range<usize>, &'a str)> {
+        self.chunks()
+            .into_iter()
+            .map(|c| (chunk_id(doc_id, c.text), c.range, c.text))
+            .collect()
+    }
+
+    /// Return an owned `SerializableChunk` for each chunk of the document `doc_id`, with its
+    /// `chunk_id`, byte offsets and whether it is a partial tail window.
+    pub fn to_serializable(&self, doc_id: &str) -> Vec<SerializableChunk> {
+        let chunks = self.chunks();
+        let full = Utf8Splitter {
+            tail: Tail::Drop,
+            ..*self
+        }
+        .chunk_count();
+        let tails = chunks.len().saturating_sub(full);
+        let first_tail = chunks.len() - tails;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, c)| SerializableChunk {
+                doc_id: doc_id.to_string(),
+                index,
+                chunk_id: format!("{:032x}", chunk_id(doc_id, c.text)),
+                start: c.range.start,
+                end: c.range.end,
+                char_len: c.char_len,
+                is_tail: index >= first_tail,
+                text: c.text.to_string(),
+            })
+            .collect()
+    }
+
+    /// Return `'static` chunks that all share `source` instead of copying their text, so the
+    /// memory used stays that of one copy of the input regardless of overlap.
+    ///
+    /// The chunk boundaries are computed over `source` with this splitter's settings; `source`
+    /// is normally the text the splitter was built over, e.g.
+    /// `Utf8Splitter::new(&src, 100, 80, true).split_shared(src.clone())`.
+    pub fn split_shared(&self, source: Arc<str>) -> Vec<ArcChunk> {
+        let ranges = self.config().splitter(&source).split_indices();
+        ranges
+            .into_iter()
+            .map(|r| ArcChunk::new(Arc::clone(&source), r))
+            .collect()
+    }
+
+    /// Return each chunk paired with its `Chunk::content_hash`, hash first; the same pairs as
+    /// `split_hashed`, computed in the same single walk.
+    pub fn split_with_hashes(&self) -> Vec<(u64, &'a str)> {
+        self.split_hashed()
+            .into_iter()
+            .map(|(text, hash)| (hash, text))
+            .collect()
+    }
+
+    /// Return each distinct chunk once, in order of first occurrence, comparing exact bytes. A
+    /// lighter `split_deduped` for when the positions of the repeats are not needed.
+    pub fn split_unique(&self) -> Vec<&'a str> {
+        let mut seen = HashSet::new();
+        self.chunk_iter().filter(|c| seen.insert(*c)).collect()
+    }
+
+    /// Return each distinct chunk once, in order of first occurrence, with the byte ranges of all
+    /// of its occurrences.
+    ///
+    /// Chunks are compared on their exact bytes, or with `normalize_whitespace` after collapsing
+    /// each run of whitespace to a single space and trimming the ends. The returned text is always
+    /// the first occurrence as it appears in the input.
+    pub fn split_deduped(&self, normalize_whitespace: bool) -> Vec<(&'a str, Vec<Range<usize>>)> {
+        let mut out: Vec<(&'a str, Vec<Range<usize>>)> = Vec::new();
+        let mut seen: HashMap<Cow<'a, str>, usize> = HashMap::new();
+        for c in self.chunks() {
+            let key = if normalize_whitespace {
+                Cow::Owned(c.text.split_whitespace().collect::<Vec<_>>().join(" "))
+            } else {
+                Cow::Borrowed(c.text)
+            };
+            match seen.get(&key) {
+                Some(&i) => out[i].1.push(c.range),
+                None => {
+                    seen.insert(key, out.len());
+                    out.push((c.text, vec![c.range]));
+                }
+            }
+        }
+        out
+    }
+
+    /// Owned version of `split_deduped`.
+    pub fn out_deduped(&self, normalize_whitespace: bool) -> Vec<(String, Vec<Range<usize>>)> {
+        self.split_deduped(normalize_whitespace)
+            .into_iter()
+            .map(|(text, ranges)| (text.to_string(), ranges))
+            .collect()
+    }
+
+    /// Return each chunk paired with its `hash_chunk` hash (the same as `Chunk::content_hash`), a
+    /// pinned FNV-1a that is stable across runs and versions.
+    ///
+    /// The hashes are computed in the same walk over the characters that finds the window
+    /// boundaries. With `trim`, `normalize_line_endings`, `avoid_breaking_clusters` or
+    /// `ansi_aware`, each chunk is hashed as it is produced instead.
+    pub fn split_hashed(&self) -> Vec<(&'a str, u64)> {
+        let input = self.input;
+        if self.trim != TrimPolicy::None
+            || self.normalize_line_endings
+            || self.keep_clusters
+            || self.ansi_aware
+        {
+            return self.chunk_iter().map(|c| (c, hash_chunk(c))).collect();
+        }
+        let num_chars = match self.char_indices {
+            Some(indices) => indices.len(),
+            None => input.chars().count(),
+        };
+        hashed_char_windows(input, num_chars, self.n, self.step, self.tail)
+            .into_iter()
+            .map(|(r, h)| (&input[r], h))
+            .filter(|(text, _)| !(self.skip_empty && text.is_empty()))
+            .filter(|(text, _)| !(self.skip_blank && text.trim().is_empty()))
+            .collect()
+    }
+
+    /// Build each owned chunk with `f(index, total, chunk)`, e.g. to wrap chunks for a prompt.
+    pub fn out_decorated(&self, f: impl Fn(usize, usize, &str) -> String) -> Vec<String> {
+        let chunks = self.split();
+        let total = chunks.len();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| f(i, total, c))
+            .collect()
+    }
+
+    /// Return owned chunks wrapped as `prefix + chunk + suffix`, each allocated once.
+    pub fn out_with_prefix_suffix(&self, prefix: &str, suffix: &str) -> Vec<String> {
+        self.split()
+            .into_iter()
+            .map(|c| {
+                let mut s = String::with_capacity(prefix.len() + c.len() + suffix.len());
+                s.push_str(prefix);
+                s.push_str(c);
+                s.push_str(suffix);
+                s
+            })
+            .collect()
+    }
+
+    /// Return all chunks joined with `sep` between them, e.g. `"\n---\n"` to preview chunk
+    /// boundaries. The result is allocated once.
+    pub fn out_joined(&self, sep: &str) -> String {
+        let chunks = self.split();
+        let len = chunks.iter().map(|c| c.len()).sum::<usize>()
+            + sep.len() * chunks.len().saturating_sub(1);
+        let mut out = String::with_capacity(len);
+        for (k, c) in chunks.iter().enumerate() {
+            if k > 0 {
+                out.push_str(sep);
+            }
+            out.push_str(c);
+        }
+        out
+    }
+
+    /// The number of chunks `split` returns, without building them.
+    ///
+    /// Only the characters are counted (or nothing, when built `from_indexed`); the count then
+    /// follows from `n`, `step` and `tail`. With `skip_empty`, `skip_blank` or
+    /// `avoid_breaking_clusters`, which drop windows by content, this falls back to splitting.
+    pub fn chunk_count(&self) -> usize {
+        if self.skip_empty || self.skip_blank || self.keep_clusters || self.ansi_aware {
+            return self.split_indices().len();
+        }
+        let num_units = if self.normalize_line_endings {
+            let body = self.input.strip_prefix('\u{FEFF}').unwrap_or(self.input);
+            body.chars().count() - body.matches("\r\n").count()
+        } else {
+            match self.char_indices {
+                Some(indices) => indices.len(),
+                None => self.input.chars().count(),
+            }
+        };
+        window_count(num_units, self.n, self.step, self.tail)
+    }
+
+    /// The number of characters each chunk shares with the next one, for every pair of
+    /// consecutive chunks (so one fewer than the chunks); `0` where they only touch or a gap
+    /// separates them.
+    pub fn overlaps(&self) -> Vec<usize> {
+        let ranges = self.split_indices();
+        ranges
+            .windows(2)
+            .map(|w| match w[0].end.checked_sub(w[1].start) {
+                Some(shared) if shared > 0 => self.input[w[1].start..w[0].end].chars().count(),
+                _ => 0,
+            })
+            .collect()
+    }
+
+    /// Chunk count, length range, mean length and total overlap, for picking `n` and `step`
+    /// empirically without aggregating over `split` by hand.
+    pub fn stats(&self) -> SplitStats {
+        let lens: Vec<usize> = self.chunk_iter().map(|c| c.chars().count()).collect();
+        if lens.is_empty() {
+            return SplitStats {
+                count: 0,
+                min_chars: 0,
+                max_chars: 0,
+                mean_chars: 0.0,
+                total_overlap_chars: 0,
+            };
+        }
+        SplitStats {
+            count: lens.len(),
+            min_chars: lens.iter().copied().min().unwrap_or(0),
+            max_chars: lens.iter().copied().max().unwrap_or(0),
+            mean_chars: lens.iter().sum::<usize>() as f64 / lens.len() as f64,
+            total_overlap_chars: self.overlaps().iter().sum(),
+        }
+    }
+
+    /// Same as `chunk_count`.
+    pub fn count(&self) -> usize {
+        self.chunk_count()
+    }
+
+    /// The `index`-th window in character positions, computed without generating the others.
+    fn window(&self, num_chars: usize, index: usize) -> Option<Range<usize>> {
+        nth_window(num_chars, self.n, self.step, self.tail, index)
+    }
+
+    /// Indices of the windows that contain the character at `byte_pos`, in increasing order.
+    /// More than one index is returned when windows overlap; none when `byte_pos` is past the end
+    /// or falls in a gap. A `byte_pos` inside a multi-byte character refers to that character.
+    ///
+    /// The window indices are found arithmetically from `n` and `step`; the only linear work is
+    /// counting the characters before `byte_pos`, which becomes a binary search when the splitter
+    /// was built `from_indexed`. Indices refer to the raw window sequence, i.e.
+    /// they match `split` when no trimming, skipping or boundary-adjusting options are set.
+    pub fn chunks_containing(&self, byte_pos: usize) -> Vec<usize> {
+        if byte_pos >= self.input.len() || self.n == 0 {
+            return Vec::new();
+        }
+        let (c, num_chars) = match self.char_indices {
+            Some(indices) => (
+                indices.partition_point(|&i| i <= byte_pos) - 1,
+                indices.len(),
+            ),
+            None => {
+                let mut c = 0usize;
+                let mut char_start = 0usize;
+                for (k, (i, _)) in self
+                    .input
+                    .char_indices()
+                    .enumerate()
+                    .take_while(|&(_, (i, _))| i <= byte_pos)
+                {
+                    (c, char_start) = (k, i);
+                }
+                (c, c + self.input[char_start..].chars().count())
+            }
+        };
+        let (n, hop) = (self.n, if self.step == 0 { self.n } else { self.step });
+        let full = window_count(num_chars, n, self.step, Tail::Drop);
+        let first = if c + 1 > n {
+            (c + 1 - n).div_ceil(hop)
+        } else {
+            0
+        };
+        let last = (c / hop).min(full.saturating_sub(1));
+        let mut out: Vec<usize> = if full > 0 && first <= last {
+            (first..=last).collect()
+        } else {
+            Vec::new()
+        };
+        if self.window(num_chars, full).is_some_and(|r| r.contains(&c)) {
+            out.push(full);
+        }
+        out
+    }
+
+    /// The `i`-th chunk, sliced without computing the others; `None` past the last chunk.
+    ///
+    /// The window comes from `nth_window`; finding its bytes takes a count of the characters
+    /// and a scan to the window's start (or indexing, when built `from_indexed`). Like
+    /// `chunks_containing`, this follows the raw window sequence.
+    pub fn nth(&self, i: usize) -> Option<&'a str> {
+        let len = self.input.len();
+        if let Some(indices) = self.char_indices {
+            let r = self.window(indices.len(), i)?;
+            let byte = |c: usize| indices.get(c).copied().unwrap_or(len);
+            return Some(&self.input[byte(r.start)..byte(r.end)]);
+        }
+        let r = self.window(self.input.chars().count(), i)?;
+        let start = self
+            .input
+            .char_indices()
+            .nth(r.start)
+            .map_or(len, |(b, _)| b);
+        Some(&self.input[start..forward_chars(self.input, start, r.len(), len)])
+    }
+
+    /// Same as `nth`.
+    pub fn chunk_at(&self, index: usize) -> Option<&'a str> {
+        self.nth(index)
+    }
+
+    /// Splits `input` into at most `k` non-overlapping chunks of `ceil(char_count / k)` characters,
+    /// the last one taking whatever remains.
+    ///
+    /// Exactly `k` chunks are produced when the last chunk is non-empty, i.e. when
+    /// `char_count > (k - 1) * ceil(char_count / k)`; otherwise fewer (9 chars with `k = 4` gives
+    /// chunks of 3, 3, 3). Use `into_k_chunks_balanced` to always get `min(k, char_count)` chunks.
+    pub fn into_k_chunks(input: &str, k: usize) -> Vec<&str> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let n = input.chars().count().div_ceil(k);
+        utf8_by_chars_borrowed(input, n.max(1), 0, true)
+    }
+
+    /// Splits `input` into `min(k, char_count)` non-overlapping chunks whose character counts
+    /// differ by at most one; the first `char_count % k` chunks get the extra character.
+    pub fn into_k_chunks_balanced(input: &str, k: usize) -> Vec<&str> {
+        let char_indices = char_offsets(input);
+        let byte = |c: usize| char_indices.get(c).copied().unwrap_or(input.len());
+        split_into_k(char_indices.len(), k)
+            .into_iter()
+            .map(|r| &input[byte(r.start)..byte(r.end)])
+            .collect()
+    }
+
+    /// Splits `input` into chunks of at most `max_chars` characters, breaking at the
+    /// highest-scoring allowed position, and returns the chunks with the chosen break offsets.
+    ///
+    /// - `candidates`: Byte offsets where a break is allowed, e.g. sentence ends. Offsets that are
+    ///   not char boundaries are ignored.
+    /// - `score`: Scores a candidate offset; higher means a better break (e.g. a topic shift).
+    /// - `max_chars`: Maximum number of characters per chunk.
+    ///
+    /// Text is packed until the next `max_chars` characters would reach past the end; the break
+    /// is then the best-scoring candidate within those characters (the later one on ties), or a
+    /// hard cut at `max_chars` when there is none. The returned offsets are the chunk ends,
+    /// excluding the end of the input.
+    pub fn with_boundary_scorer(
+        input: &'a str,
+        candidates: &[usize],
+        score: impl Fn(usize) -> f32,
+        max_chars: usize,
+    ) -> (Vec<&'a str>, Vec<usize>) {
+        let len = input.len();
+        let max = max_chars.max(1);
+        let mut chunks = Vec::new();
+        let mut breaks = Vec::new();
+        let mut start = 0usize;
+
+        while start < len {
+            let hard_end = forward_chars(input, start, max, len);
+            if hard_end == len {
+                chunks.push(&input[start..len]);
+                break;
+            }
+            let end = candidates
+                .iter()
+                .copied()
+                .filter(|&c| start < c && c <= hard_end && input.is_char_boundary(c))
+                .map(|c| (score(c), c))
+                .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+                .map_or(hard_end, |(_, c)| c);
+            chunks.push(&input[start..end]);
+            breaks.push(end);
+            start = end;
+        }
+        (chunks, breaks)
+    }
+
+    /// Splits `input` on `delimiter`, then windows each segment independently, returning
+    /// `(segment_index, chunk_index_within_segment, chunk)` triples in document order.
+    ///
+    /// - `delimiter`: The segment separator, e.g. a `---` page marker. It never appears in a chunk.
+    /// - `n`, `step`, `keep_tail`: As in `new`, applied per segment, so overlap never crosses a
+    ///   delimiter.
+    ///
+    /// Empty segments produce no chunks, but still count towards `segment_index`, which is the
+    /// position of the segment in `input` (the page number, for page markers).
+    pub fn per_segment(
+        input: &'a str,
+        delimiter: &str,
+        n: usize,
+        step: usize,
+        keep_tail: bool,
+    ) -> Vec<(usize, usize, &'a str)> {
+        let segments: Vec<&'a str> = if delimiter.is_empty() {
+            vec![input]
+        } else {
+            input.split(delimiter).collect()
+        };
+        let mut out = Vec::new();
+        for (seg, text) in segments.into_iter().enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            let chunks = utf8_by_chars_borrowed(text, n, step, keep_tail);
+            out.extend(chunks.into_iter().enumerate().map(|(k, c)| (seg, k, c)));
+        }
+        out
+    }
+
+    /// Splits `input` into non-overlapping chunks of at most `max_bytes` bytes, each made of
+    /// whole characters: characters are added until the next one would push the chunk over the
+    /// limit. A single character wider than `max_bytes` becomes a chunk of its own.
+    pub fn split_balanced_bytes(input: &str, max_bytes: usize) -> Vec<&str> {
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        for (i, c) in input.char_indices() {
+            if i > start && i + c.len_utf8() - start > max_bytes {
+                out.push(&input[start..i]);
+                start = i;
+            }
+        }
+        if start < input.len() {
+            out.push(&input[start..]);
+        }
+        out
+    }
+
+    /// Splits plain text into chunks of at most `max_chars` characters that end at a line break
+    /// when possible.
+    ///
+    /// - `max_chars`: Maximum number of characters per chunk.
+    /// - `overlap`: Characters repeated from the end of one chunk at the start of the next.
+    ///
+    /// Each chunk is cut just after the last `\n` within its `max_chars` characters, else just
+    /// after the last space, else hard at `max_chars`; a line longer than `max_chars` is thus
+    /// broken at spaces. Like `SmartSplitter` with a fallback separator, without the full
+    /// separator hierarchy.
+    pub fn split_prefer_newlines(input: &str, max_chars: usize, overlap: usize) -> Vec<&str> {
+        let len = input.len();
+        let max = max_chars.max(1);
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        while start < len {
+            let hard_end = forward_chars(input, start, max, len);
+            if hard_end == len {
+                out.push(&input[start..]);
+                break;
+            }
+            let window = &input[start..hard_end];
+            let end = window
+                .rfind('\n')
+                .or_else(|| window.rfind(' '))
+                .map_or(hard_end, |i| start + i + 1);
+            out.push(&input[start..end]);
+
+            // Step back by the overlap, but always past the previous start.
+            let floor = forward_chars(input, start, 1, len);
+            start = back_chars(input, end, overlap, floor);
+        }
+        out
+    }
+
+    /// Splits `input` into windows of `target` characters overlapping by `overlap`, then grows
+    /// every chunk shorter than `min` characters leftward until it has `min` characters or
+    /// starts at the beginning of `input`.
+    ///
+    /// - `target`: Number of characters per window (as `n` in `new`).
+    /// - `min`: Minimum number of characters per chunk, e.g. for embedding models that give poor
+    ///   vectors for very short inputs.
+    /// - `overlap`: Characters shared by consecutive windows; clamped so the step is at least 1.
+    ///
+    /// With `min <= target` only the tail can come out of windowing short, so this extends the
+    /// last chunk, which then overlaps the one before it by more than `overlap`. With `min` above
+    /// `target` every chunk not at the start grows and overlaps its neighbor. An input shorter
+    /// than `min` stays one short chunk.
+    pub fn split_min_size(input: &str, target: usize, min: usize, overlap: usize) -> Vec<&str> {
+        let step = target.saturating_sub(overlap).max(1);
+        utf8_by_chars_indices(input, target, step, Tail::Keep)
+            .into_iter()
+            .map(|r| {
+                let start = if input[r.clone()].chars().count() < min {
+                    back_chars(input, r.end, min, 0)
+                } else {
+                    r.start
+                };
+                &input[start.min(r.start)..r.end]
+            })
+            .collect()
+    }
+
+    /// Splits `input` at externally computed character positions, e.g. from a sentence segmenter,
+    /// returning the text before the first position, between consecutive positions, and after the
+    /// last one.
+    ///
+    /// - `char_positions`: Character (not byte) offsets in `0..=char_count`, in non-decreasing
+    ///   order. Each is converted to the byte offset where that character starts, so every cut
+    ///   falls on a char boundary.
+    ///
+    /// Empty pieces (from repeated positions, or positions at either end) are left out. Unsorted
+    /// or out-of-range positions are rejected.
+    pub fn split_at(input: &'a str, char_positions: &[usize]) -> Result<Vec<&'a str>, SplitError> {
+        if let Some(index) =
+            (1..char_positions.len()).find(|&i| char_positions[i] < char_positions[i - 1])
+        {
+            return Err(SplitError::UnsortedPositions { index });
+        }
+
+        let mut cuts = Vec::with_capacity(char_positions.len() + 2);
+        cuts.push(0);
+        let mut wanted = char_positions.iter().copied().peekable();
+        let mut num_chars = 0usize;
+        for (c, (b, _)) in input.char_indices().enumerate() {
+            while wanted.next_if_eq(&c).is_some() {
+                cuts.push(b);
+            }
+            num_chars = c + 1;
+        }
+        while wanted.next_if_eq(&num_chars).is_some() {
+            cuts.push(input.len());
+        }
+        if let Some(position) = wanted.next() {
+            return Err(SplitError::PositionOutOfRange {
+                position,
+                len: num_chars,
+            });
+        }
+        cuts.push(input.len());
+
+        Ok(cuts
+            .windows(2)
+            .filter(|w| w[0] < w[1])
+            .map(|w| &input[w[0]..w[1]])
+            .collect())
+    }
+
+    /// Like `split`, but `n` and `step` count UTF-16 code units (see `utf16_by_units_borrowed`).
+    pub fn split_utf16(&self) -> Vec<&'a str> {
+        utf16_by_units_borrowed(self.input, self.n, self.step, self.tail)
+    }
+
+    /// Like `split`, but each window is extended leftward by up to `prefix_chars` characters
+    /// (clamped at the start of the input, after a BOM skipped by `normalize_line_endings`). The
+    /// window boundaries themselves are unchanged, including the effect of `trim`, `skip_empty`
+    /// and the other options of `split_indices`; only the returned spans grow to include a
+    /// lookbehind into the previous chunk.
+    pub fn split_with_prefix(&self, prefix_chars: usize) -> Vec<&'a str> {
+        let input = self.input;
+        let floor = if self.normalize_line_endings && input.starts_with('\u{FEFF}') {
+            '\u{FEFF}'.len_utf8()
+        } else {
+            0
+        };
+        self.split_indices()
+            .into_iter()
+            .map(|r| &input[back_chars(input, r.start, prefix_chars, floor.min(r.start))..r.end])
+            .collect()
+    }
+
+    /// Like `split`, but each window except the last is extended rightward by up to
+    /// `suffix_chars` characters (clamped at the end of the input). The window boundaries
+    /// themselves are unchanged, including the effect of `trim`, `skip_empty` and the other
+    /// options of `split_indices`; only the returned spans grow to include a peek into the next
+    /// chunk.
+    pub fn split_with_suffix(&self, suffix_chars: usize) -> Vec<&'a str> {
+        let input = self.input;
+        let ranges = self.split_indices();
+        let last = ranges.len().saturating_sub(1);
+        ranges
+            .into_iter()
+            .enumerate()
+            .map(|(k, r)| {
+                let end = if k < last {
+                    forward_chars(input, r.end, suffix_chars, input.len())
+                } else {
+                    r.end
+                };
+                &input[r.start..end]
+            })
+            .collect()
+    }
+
+    /// Like `split`, but windows are laid out from the end of the input backward, so the last
+    /// window ends at the last character and any short tail piece is at the front.
+    ///
+    /// Chunks are returned in document order; see `split_from_end_rev` for back-to-front order.
+    pub fn split_from_end(&self) -> Vec<&'a str> {
+        let mut out = self.split_from_end_rev();
+        out.reverse();
+        out
+    }
+
+    /// Same as `split_from_end`: keeps the most recent text in whole chunks, the final chunk
+    /// always ending with the input.
+    ///
+    /// The tail policy applies to the *leading* chunk: `Tail::Keep` (`keep_tail = true`) emits
+    /// the leftover oldest characters as a short first chunk, `Tail::Drop` discards them, and
+    /// `Tail::PadBackward` extends that first chunk forward to a full `n` characters from the
+    /// start of the input.
+    pub fn rsplit(&self) -> Vec<&'a str> {
+        self.split_from_end()
+    }
+
+    /// Same as `split_from_end`, but emitted starting from the end of the input.
+    pub fn split_from_end_rev(&self) -> Vec<&'a str> {
+        let char_indices = self.char_starts();
+        let num_chars = char_indices.len();
+        let byte = |c: usize| char_indices.get(c).copied().unwrap_or(self.input.len());
+        sliding_windows(num_chars, self.n, self.step, self.tail)
+            .map(|r| &self.input[byte(num_chars - r.end)..byte(num_chars - r.start)])
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    fn any_tail() -> impl Strategy<Value = Tail> {
+        prop::sample::select(vec![Tail::Drop, Tail::Keep, Tail::PadBackward, Tail::Exact])
+    }
 
     #[test]
     fn simple_ascii_nonoverlapping() {
@@ -101,4 +1563,1035 @@ mod tests {
         let v = utf8_by_chars_borrowed(s, 3, 0, true);
         assert_eq!(v, vec!["abc", "def", "g"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn pad_backward_equal_lengths() {
+        let s = "a😀b😃cde";
+        let v = Utf8Splitter::with_tail(s, 3, 0, Tail::PadBackward).split();
+        assert_eq!(v, vec!["a😀b", "😃cd", "cde"]);
+    }
+
+    #[test]
+    fn prefix_lookbehind() {
+        let s = "a😀b😃cdef";
+        let v = Utf8Splitter::new(s, 3, 0, true).split_with_prefix(2);
+        assert_eq!(v, vec!["a😀b", "😀b😃cd", "cdef"]);
+    }
+
+    #[test]
+    fn zero_prefix_matches_split() {
+        let sp = Utf8Splitter::new("abcdefg", 3, 2, true);
+        assert_eq!(sp.split_with_prefix(0), sp.split());
+        let sp = Utf8Splitter::new(" ab  cd ", 3, 0, true)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true);
+        assert_eq!(sp.split_with_prefix(0), sp.split());
+        assert_eq!(sp.split_with_prefix(1), vec![" ab", " c", "cd"]);
+    }
+
+    #[test]
+    fn prefix_with_empty_windows_and_empty_input() {
+        let sp = Utf8Splitter::new("abc", 0, 1, true);
+        assert_eq!(sp.split_with_prefix(0), vec!["", "", "", ""]);
+        assert_eq!(sp.split_with_prefix(1), vec!["", "a", "b", "c"]);
+        assert!(Utf8Splitter::new("", 3, 0, true)
+            .split_with_prefix(2)
+            .is_empty());
+        let bom = Utf8Splitter::new("\u{FEFF}abcd", 2, 0, true).normalize_line_endings(true);
+        assert_eq!(bom.split_with_prefix(3), vec!["ab", "abcd"]);
+    }
+
+    #[test]
+    fn utf16_ascii_matches_chars() {
+        let s = "abcdefg";
+        assert_eq!(
+            utf16_by_units_borrowed(s, 3, 0, true),
+            utf8_by_chars_borrowed(s, 3, 0, true)
+        );
+        assert_eq!(
+            utf16_by_units_borrowed(s, 3, 1, false),
+            utf8_by_chars_borrowed(s, 3, 1, false)
+        );
+    }
+
+    #[test]
+    fn utf16_pad_backward_with_overlapping_step() {
+        assert_eq!(
+            utf16_by_units_borrowed("abcdef", 4, 2, Tail::PadBackward),
+            vec!["abcd", "cdef"]
+        );
+        assert_eq!(
+            utf16_by_units_borrowed("abcdefg", 4, 2, Tail::PadBackward),
+            vec!["abcd", "cdef", "defg"]
+        );
+    }
+
+    #[test]
+    fn utf16_astral_counts_two_units() {
+        let s = "a😀bc😃";
+        // units: a=1, 😀=2, b=1, c=1, 😃=2 (total 7)
+        let v = utf16_by_units_borrowed(s, 3, 0, true);
+        assert_eq!(v, vec!["a😀", "bc", "😃"]);
+        let v = utf16_by_units_borrowed(s, 2, 0, true);
+        // "a" is one unit short because 😀 straddles the boundary.
+        assert_eq!(v, vec!["a", "😀", "bc", "😃"]);
+        assert_eq!(v.concat(), s);
+    }
+
+    #[test]
+    fn utf16_single_unit_windows_keep_astral_chars() {
+        let v = Utf8Splitter::new("x😀y", 1, 0, true).split_utf16();
+        assert_eq!(v, vec!["x", "😀", "y"]);
+    }
+
+    #[test]
+    fn k_chunks_ceil() {
+        assert_eq!(
+            Utf8Splitter::into_k_chunks("abcdefghij", 4),
+            vec!["abc", "def", "ghi", "j"]
+        );
+        assert_eq!(
+            Utf8Splitter::into_k_chunks("abcdefghi", 4),
+            vec!["abc", "def", "ghi"]
+        );
+        assert_eq!(Utf8Splitter::into_k_chunks("ab", 5), vec!["a", "b"]);
+        assert!(Utf8Splitter::into_k_chunks("abc", 0).is_empty());
+        assert!(Utf8Splitter::into_k_chunks("", 3).is_empty());
+    }
+
+    #[test]
+    fn k_chunks_balanced() {
+        assert_eq!(
+            Utf8Splitter::into_k_chunks_balanced("abcdefghij", 4),
+            vec!["abc", "def", "gh", "ij"]
+        );
+        assert_eq!(
+            Utf8Splitter::into_k_chunks_balanced("a😀b😃c", 2),
+            vec!["a😀b", "😃c"]
+        );
+        assert_eq!(
+            Utf8Splitter::into_k_chunks_balanced("ab", 5),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn trimmed_indices_match_slices() {
+        let s = "ab \n  cd\n\n  \n ef";
+        let sp = Utf8Splitter::new(s, 4, 0, true).trim(TrimPolicy::Both);
+        let v = sp.split();
+        assert_eq!(v, vec!["ab", "cd", "", "ef"]);
+        for (r, c) in sp.split_indices().into_iter().zip(&v) {
+            assert_eq!(&s[r], *c);
+        }
+        let sp = sp.skip_empty(true);
+        assert_eq!(sp.split(), vec!["ab", "cd", "ef"]);
+        assert_eq!(sp.split_indices(), vec![0..2, 6..8, 14..16]);
+    }
+
+    #[test]
+    fn trim_start_and_end_only() {
+        let s = " ab  cd ";
+        assert_eq!(
+            Utf8Splitter::new(s, 4, 0, true)
+                .trim(TrimPolicy::Start)
+                .split(),
+            vec!["ab ", "cd "]
+        );
+        assert_eq!(
+            Utf8Splitter::new(s, 4, 0, true)
+                .trim(TrimPolicy::End)
+                .split(),
+            vec![" ab", " cd"]
+        );
+    }
+
+    #[test]
+    fn skip_blank_renumbers_densely() {
+        let s = "ab\n\n\n\ncd  \n\nef";
+        let sp = Utf8Splitter::new(s, 2, 0, true).skip_blank(true);
+        let v = sp.split();
+        assert_eq!(v, vec!["ab", "cd", "ef"]);
+        let indexed: Vec<_> = v.iter().enumerate().map(|(i, c)| (i, *c)).collect();
+        assert_eq!(indexed, vec![(0, "ab"), (1, "cd"), (2, "ef")]);
+        assert_eq!(sp.split_indices(), vec![0..2, 6..8, 12..14]);
+    }
+
+    #[test]
+    fn decorated_output() {
+        let sp = Utf8Splitter::new("abcdefg", 3, 0, true);
+        let v = sp.out_decorated(|i, total, c| format!("[part {}/{}]\n{}", i + 1, total, c));
+        assert_eq!(
+            v,
+            vec!["[part 1/3]\nabc", "[part 2/3]\ndef", "[part 3/3]\ng"]
+        );
+        assert_eq!(
+            sp.out_with_prefix_suffix("<", ">"),
+            vec!["<abc>", "<def>", "<g>"]
+        );
+    }
+
+    #[test]
+    fn crlf_and_lf_boundaries_align() {
+        let lf = "one\ntwo\n\nthree\nfour";
+        let crlf = format!("\u{FEFF}{}", lf.replace('\n', "\r\n"));
+        for (n, step) in [(3, 0), (4, 2), (5, 0)] {
+            let a = Utf8Splitter::new(lf, n, step, true)
+                .normalize_line_endings(true)
+                .split();
+            let b = Utf8Splitter::new(&crlf, n, step, true)
+                .normalize_line_endings(true)
+                .split();
+            let b: Vec<_> = b.iter().map(|c| c.replace("\r\n", "\n")).collect();
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn crlf_kept_in_output() {
+        let s = "\u{FEFF}ab\r\ncd";
+        let v = Utf8Splitter::new(s, 3, 0, true)
+            .normalize_line_endings(true)
+            .split();
+        assert_eq!(v, vec!["ab\r\n", "cd"]);
+    }
+
+    #[test]
+    fn suffix_peek() {
+        let s = "a😀b😃cdef";
+        let v = Utf8Splitter::new(s, 3, 0, true).split_with_suffix(2);
+        assert_eq!(v, vec!["a😀b😃c", "😃cdef", "ef"]);
+        let v = Utf8Splitter::new("abcdefg", 3, 0, false).split_with_suffix(5);
+        assert_eq!(v, vec!["abcdefg", "def"]);
+    }
+
+    #[test]
+    fn suffix_with_empty_windows_and_empty_input() {
+        let sp = Utf8Splitter::new("abc", 0, 1, true);
+        assert_eq!(sp.split_with_suffix(0), sp.split());
+        assert_eq!(sp.split_with_suffix(1), vec!["a", "b", "c", ""]);
+        assert!(Utf8Splitter::new("", 3, 0, true)
+            .split_with_suffix(2)
+            .is_empty());
+        let trimmed = Utf8Splitter::new("ab  cd ", 3, 0, true)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true);
+        assert_eq!(trimmed.split_with_suffix(0), trimmed.split());
+        assert_eq!(trimmed.split_with_suffix(1), vec!["ab ", "cd"]);
+    }
+
+    #[test]
+    fn hashes_are_stable_per_content() {
+        let v = Utf8Splitter::new("abcabcab", 3, 0, true).split_with_hashes();
+        assert_eq!(
+            v.iter().map(|&(_, c)| c).collect::<Vec<_>>(),
+            vec!["abc", "abc", "ab"]
+        );
+        assert_eq!(v[0].0, v[1].0);
+        assert_ne!(v[0].0, v[2].0);
+        assert_eq!(v[0].0, crate::splitter::utils::stable_hash(b"abc"));
+        assert!(v
+            .iter()
+            .all(|&(h, c)| h == Chunk::new(c, 0..c.len()).content_hash()));
+    }
+
+    #[test]
+    fn avoid_breaking_clusters_nudges_forward() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let s = format!("ab{family}cde\u{0301}f");
+        let plain = Utf8Splitter::new(&s, 3, 0, true).split();
+        assert_eq!(plain[0], "ab👨");
+        let sp = Utf8Splitter::new(&s, 3, 0, true).avoid_breaking_clusters(true);
+        let v = sp.split();
+        assert_eq!(v, vec![format!("ab{family}").as_str(), "cd", "e\u{0301}f"]);
+        assert_eq!(v.concat(), s);
+        let ranges = sp.split_indices();
+        assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn avoid_breaking_clusters_keeps_flags_and_hangul_whole() {
+        let flags = "\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}\u{1F1EF}\u{1F1F5}";
+        let v = Utf8Splitter::new(flags, 3, 0, true)
+            .avoid_breaking_clusters(true)
+            .split();
+        assert_eq!(
+            v,
+            vec!["\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}", "\u{1F1EF}\u{1F1F5}"]
+        );
+
+        // Two syllables spelled with conjoining jamo: 한 (3 jamo) and 글 (3 jamo).
+        let hangul = "\u{1112}\u{1161}\u{11AB}\u{1100}\u{1173}\u{11AF}";
+        let v = Utf8Splitter::new(hangul, 2, 0, true)
+            .avoid_breaking_clusters(true)
+            .split();
+        assert_eq!(
+            v,
+            vec!["\u{1112}\u{1161}\u{11AB}", "\u{1100}\u{1173}\u{11AF}"]
+        );
+    }
+
+    #[test]
+    fn avoid_breaking_clusters_keeps_plain_text_unchanged() {
+        let sp = Utf8Splitter::new("abcdefg", 3, 1, true);
+        assert_eq!(
+            sp.split(),
+            Utf8Splitter::new("abcdefg", 3, 1, true)
+                .avoid_breaking_clusters(true)
+                .split()
+        );
+    }
+
+    #[test]
+    fn chunks_containing_matches_ranges() {
+        let s = "a😀bcdéfgh";
+        for (n, step, tail) in [
+            (3, 0, Tail::Keep),
+            (4, 2, Tail::Keep),
+            (3, 2, Tail::PadBackward),
+            (2, 3, Tail::Drop),
+        ] {
+            let sp = Utf8Splitter::with_tail(s, n, step, tail);
+            let ranges = sp.split_indices();
+            for pos in 0..s.len() + 2 {
+                let c = (0..=pos.min(s.len()))
+                    .rev()
+                    .find(|&i| s.is_char_boundary(i))
+                    .unwrap();
+                let expected: Vec<usize> = (0..ranges.len())
+                    .filter(|&k| pos < s.len() && ranges[k].start <= c && c < ranges[k].end)
+                    .collect();
+                assert_eq!(
+                    sp.chunks_containing(pos),
+                    expected,
+                    "n={n} step={step} pos={pos}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_at_matches_split() {
+        for (n, step, tail) in [
+            (3, 0, Tail::Keep),
+            (4, 2, Tail::PadBackward),
+            (3, 0, Tail::Drop),
+        ] {
+            let sp = Utf8Splitter::with_tail("a😀bcdéfgh", n, step, tail);
+            let all = sp.split();
+            for (k, c) in all.iter().enumerate() {
+                assert_eq!(sp.chunk_at(k), Some(*c));
+            }
+            assert_eq!(sp.chunk_at(all.len()), None);
+        }
+    }
+
+    #[test]
+    fn indexed_str_windows_match_unindexed() {
+        let s = "a😀b😃cdéfgh";
+        let indexed = IndexedStr::new(s);
+        assert_eq!(indexed.num_chars(), 10);
+        for (n, step) in [(3, 0), (4, 2), (2, 3)] {
+            assert_eq!(
+                indexed.windows(n, step, true),
+                utf8_by_chars_borrowed(s, n, step, true)
+            );
+            let a = Utf8Splitter::from_indexed(&indexed, n, step, Tail::Keep);
+            let b = Utf8Splitter::with_tail(s, n, step, Tail::Keep);
+            assert_eq!(a.split(), b.split());
+            assert_eq!(a.split_with_prefix(1), b.split_with_prefix(1));
+            for pos in 0..s.len() {
+                assert_eq!(a.chunks_containing(pos), b.chunks_containing(pos));
+            }
+            for k in 0..6 {
+                assert_eq!(a.chunk_at(k), b.chunk_at(k));
+            }
+            let (a, b) = (a.trim(TrimPolicy::Both), b.trim(TrimPolicy::Both));
+            let mut visited = Vec::new();
+            a.for_each_chunk(|_, c| {
+                visited.push(c);
+                ControlFlow::Continue(())
+            });
+            assert_eq!(visited, b.split());
+            assert_eq!(a.map(|c| c).collect::<Vec<_>>(), b.split());
+        }
+    }
+
+    #[test]
+    fn nth_addresses_every_window_including_tail() {
+        let s = "a😀bcdéfgh";
+        for (n, step, tail) in [
+            (3, 0, Tail::Keep),
+            (4, 2, Tail::PadBackward),
+            (3, 0, Tail::Drop),
+            (9, 0, Tail::Keep),
+        ] {
+            let sp = Utf8Splitter::with_tail(s, n, step, tail);
+            let all = sp.split();
+            for (k, c) in all.iter().enumerate() {
+                assert_eq!(sp.nth(k), Some(*c), "n={n} step={step} k={k}");
+            }
+            assert_eq!(sp.nth(all.len()), None);
+            assert_eq!(sp.nth(usize::MAX), None);
+        }
+    }
+
+    #[test]
+    fn split_trimmed_keeps_untrimmed_spans() {
+        let s = "ab  \u{a0}   cd é ";
+        let sp = Utf8Splitter::new(s, 4, 0, true);
+        assert_eq!(sp.split(), vec!["ab  ", "\u{a0}   ", "cd é", " "]);
+        assert_eq!(sp.split_trimmed(), vec!["ab", "cd é"]);
+        let spans = sp.split_with_spans_trimmed();
+        assert_eq!(spans[0], ("ab", 0..4));
+        assert_eq!(&s[spans[1].1.clone()], "cd é");
+        assert_eq!(spans.len(), 2);
+    }
+
+    proptest! {
+        #[test]
+        fn chunk_count_matches_split_len(
+            chars in 0..24usize,
+            n in 1..9usize,
+            step in 0..10usize,
+            tail in any_tail(),
+            normalize: bool,
+            skip_blank: bool,
+        ) {
+            let text = "a😀b\r\nc é\u{301}d  \r\n\u{FEFF}xyzw🙂 q";
+            let end = text.char_indices().nth(chars).map_or(text.len(), |(i, _)| i);
+            let sp = Utf8Splitter::with_tail(&text[..end], n, step, tail)
+                .normalize_line_endings(normalize)
+                .skip_blank(skip_blank);
+            prop_assert_eq!(sp.chunk_count(), sp.split().len());
+        }
+    }
+
+    #[test]
+    fn split_from_end_anchors_last_chunk() {
+        let s = "xab😀cdéfgh";
+        let sp = Utf8Splitter::new(s, 3, 0, true);
+        assert_eq!(sp.split_from_end(), vec!["x", "ab😀", "cdé", "fgh"]);
+        assert_eq!(sp.split_from_end_rev(), vec!["fgh", "cdé", "ab😀", "x"]);
+        let dropped = Utf8Splitter::new(s, 3, 0, false).split_from_end();
+        assert_eq!(dropped, vec!["ab😀", "cdé", "fgh"]);
+        let overlapping = Utf8Splitter::new(s, 4, 2, true).split_from_end();
+        assert_eq!(overlapping, vec!["xa", "xab😀", "b😀cd", "cdéf", "éfgh"]);
+    }
+
+    #[test]
+    fn rsplit_tail_is_leading_chunk() {
+        let s = "héllo wörld";
+        let rsplit = |tail| Utf8Splitter::with_tail(s, 4, 0, tail).rsplit();
+        assert_eq!(rsplit(Tail::Keep), vec!["hél", "lo w", "örld"]);
+        assert_eq!(rsplit(Tail::Drop), vec!["lo w", "örld"]);
+        assert_eq!(rsplit(Tail::PadBackward), vec!["héll", "lo w", "örld"]);
+    }
+
+    #[test]
+    fn per_segment_windows_each_segment() {
+        let s = "abcdé---------xyz---";
+        let v = Utf8Splitter::per_segment(s, "---", 2, 0, true);
+        assert_eq!(
+            v,
+            vec![
+                (0, 0, "ab"),
+                (0, 1, "cd"),
+                (0, 2, "é"),
+                (3, 0, "xy"),
+                (3, 1, "z"),
+            ]
+        );
+        let overlapping = Utf8Splitter::per_segment("abc|de", "|", 2, 1, false);
+        assert_eq!(overlapping, vec![(0, 0, "ab"), (0, 1, "bc"), (1, 0, "de")]);
+    }
+
+    #[test]
+    fn boundary_scorer_prefers_high_scores() {
+        let s = "Aa. Bb. Cc. Dd. Ee.";
+        let candidates = [4, 8, 12, 16];
+        let score = |c: usize| if c == 8 { 0.9 } else { 0.1 };
+        let (chunks, breaks) = Utf8Splitter::with_boundary_scorer(s, &candidates, score, 12);
+        assert_eq!(chunks, vec!["Aa. Bb. ", "Cc. Dd. Ee."]);
+        assert_eq!(breaks, vec![8]);
+    }
+
+    #[test]
+    fn boundary_scorer_hard_cuts_without_candidates() {
+        let s = "ééééééé";
+        let (chunks, breaks) = Utf8Splitter::with_boundary_scorer(s, &[1, 3], |_| 1.0, 3);
+        assert_eq!(chunks, vec!["ééé", "ééé", "é"]);
+        assert_eq!(breaks, vec![6, 12]);
+    }
+
+    #[test]
+    fn dedup_groups_repeated_chunks() {
+        let s = "HEADabcdHEADefghHEAD";
+        let v = Utf8Splitter::new(s, 4, 0, true).split_deduped(false);
+        let texts: Vec<_> = v.iter().map(|(t, _)| *t).collect();
+        assert_eq!(texts, vec!["HEAD", "abcd", "efgh"]);
+        assert_eq!(v[0].1, vec![0..4, 8..12, 16..20]);
+        assert_eq!(v[2].1.len(), 1);
+    }
+
+    #[test]
+    fn dedup_can_ignore_whitespace_runs() {
+        let sp = Utf8Splitter::new("a b a  b", 4, 0, true);
+        assert_eq!(sp.split_deduped(false).len(), 2);
+        let loose = sp.out_deduped(true);
+        assert_eq!(loose, vec![("a b ".to_string(), vec![0..4, 4..8])]);
+    }
+
+    #[test]
+    fn balanced_bytes_stay_under_limit() {
+        let s = "ab😀cdef😀😀gh😀ijkl";
+        let chunks = Utf8Splitter::split_balanced_bytes(s, 8);
+        assert_eq!(chunks.concat(), s);
+        assert_eq!(chunks, vec!["ab😀cd", "ef😀", "😀gh", "😀ijkl"]);
+        assert!(chunks.iter().all(|c| c.len() <= 8 && c.len() >= 6));
+        // The same text by character count gives far less even byte sizes.
+        let by_chars: Vec<_> = Utf8Splitter::new(s, 4, 0, true)
+            .split()
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        assert_eq!(by_chars, vec![7, 7, 10, 4]);
+        assert_eq!(
+            Utf8Splitter::split_balanced_bytes("😀a", 2),
+            vec!["😀", "a"]
+        );
+    }
+
+    #[test]
+    fn split_hashed_matches_hash_chunk() {
+        let sp = Utf8Splitter::new("abcabcab", 3, 0, true);
+        let v = sp.split_hashed();
+        assert_eq!(v[0].1, v[1].1);
+        assert_ne!(v[0].1, v[2].1);
+        for (c, h) in v {
+            assert_eq!(h, hash_chunk(c));
+        }
+
+        let text = "añb😀 cdé\r\nfg  h";
+        for (n, step) in [(1, 0), (3, 0), (4, 1), (4, 2), (2, 5), (0, 2)] {
+            for tail in [Tail::Keep, Tail::Drop, Tail::PadBackward] {
+                for sp in [
+                    Utf8Splitter::with_tail(text, n, step, tail),
+                    Utf8Splitter::with_tail(text, n, step, tail).skip_blank(true),
+                    Utf8Splitter::with_tail(text, n, step, tail).trim(TrimPolicy::Both),
+                ] {
+                    let v = sp.split_hashed();
+                    assert_eq!(v.iter().map(|&(c, _)| c).collect::<Vec<_>>(), sp.split());
+                    assert!(v.iter().all(|&(c, h)| h == hash_chunk(c)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ids_survive_edits_elsewhere() {
+        let before = Utf8Splitter::new("aaaa bbbb cccc", 5, 0, true).split_with_ids("doc");
+        let after = Utf8Splitter::new("aaaa bbbb CCCC", 5, 0, true).split_with_ids("doc");
+        assert_eq!(before[0].0, after[0].0);
+        assert_eq!(before[1].0, after[1].0);
+        assert_ne!(before[2].0, after[2].0);
+        assert_eq!(before[1].1, 5..10);
+        assert_eq!(before[1].0, chunk_id("doc", "bbbb "));
+    }
+
+    #[test]
+    fn out_joined_inserts_separator_between_chunks() {
+        let sp = Utf8Splitter::new("abcdé", 2, 0, true);
+        let joined = sp.out_joined("\n---\n");
+        assert_eq!(joined, "ab\n---\ncd\n---\né");
+        assert_eq!(Utf8Splitter::new("", 2, 0, true).out_joined("|"), "");
+    }
+
+    #[test]
+    fn try_split_rejects_zero_window() {
+        let s = "abc";
+        assert_eq!(
+            Utf8Splitter::new(s, 0, 1, true).try_split(),
+            Err(SplitError::ZeroWindow)
+        );
+        assert_eq!(
+            Utf8Splitter::new(s, 0, 0, true).try_out(),
+            Err(SplitError::ZeroWindowAndStep)
+        );
+        assert_eq!(
+            Utf8Splitter::new(s, 2, 0, true).try_split(),
+            Ok(vec!["ab", "c"])
+        );
+    }
+
+    #[test]
+    fn split_at_char_positions() {
+        let s = "Hé. 😀 ok.";
+        assert_eq!(
+            Utf8Splitter::split_at(s, &[3, 6]),
+            Ok(vec!["Hé.", " 😀 ", "ok."])
+        );
+        assert_eq!(
+            Utf8Splitter::split_at(s, &[0, 3, 3, 9]),
+            Ok(vec!["Hé.", " 😀 ok."])
+        );
+        assert_eq!(Utf8Splitter::split_at(s, &[]), Ok(vec![s]));
+        assert_eq!(
+            Utf8Splitter::split_at(s, &[4, 2]),
+            Err(SplitError::UnsortedPositions { index: 1 })
+        );
+        assert_eq!(
+            Utf8Splitter::split_at(s, &[2, 10]),
+            Err(SplitError::PositionOutOfRange {
+                position: 10,
+                len: 9
+            })
+        );
+    }
+
+    #[test]
+    fn map_lowercases_lazily() {
+        let lower: Vec<String> = Utf8Splitter::new("HeLLo WÖRLD", 4, 0, true)
+            .map(str::to_lowercase)
+            .collect();
+        assert_eq!(lower, vec!["hell", "o wö", "rld"]);
+        let first_long: Vec<usize> = Utf8Splitter::new("ab cd efgh", 2, 1, true)
+            .map(|c| c.trim().len())
+            .filter(|&n| n == 2)
+            .take(2)
+            .collect();
+        assert_eq!(first_long, vec![2, 2]);
+
+        let calls = std::cell::Cell::new(0);
+        let firsts: Vec<&str> = Utf8Splitter::new("abcdefgh", 2, 0, true)
+            .map(|c| {
+                calls.set(calls.get() + 1);
+                c
+            })
+            .take(2)
+            .collect();
+        assert_eq!(firsts, vec!["ab", "cd"]);
+        assert_eq!(calls.get(), 2);
+
+        let sp = || Utf8Splitter::new(" a  b c ", 2, 1, true).trim(TrimPolicy::Both);
+        let mapped: Vec<&str> = sp().skip_empty(true).map(|c| c).collect();
+        assert_eq!(mapped, sp().skip_empty(true).split());
+    }
+
+    #[test]
+    fn for_each_chunk_matches_split_and_stops_early() {
+        let s = "a😀 b\r\ncdé  fgh";
+        for (n, step, tail) in [
+            (3, 0, Tail::Keep),
+            (4, 2, Tail::PadBackward),
+            (5, 3, Tail::Drop),
+        ] {
+            for sp in [
+                Utf8Splitter::with_tail(s, n, step, tail),
+                Utf8Splitter::with_tail(s, n, step, tail)
+                    .trim(TrimPolicy::Both)
+                    .skip_empty(true),
+                Utf8Splitter::with_tail(s, n, step, tail).normalize_line_endings(true),
+            ] {
+                let mut seen = Vec::new();
+                sp.for_each_chunk(|k, c| {
+                    assert_eq!(k, seen.len());
+                    seen.push(c);
+                    ControlFlow::Continue(())
+                });
+                assert_eq!(seen, sp.split());
+            }
+        }
+
+        let mut calls = 0;
+        Utf8Splitter::new("abcdefg", 3, 0, true).for_each_chunk(|k, _| {
+            calls += 1;
+            if k == 1 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn split_with_meta_clones_into_every_chunk() {
+        use std::sync::Arc;
+
+        let meta = Arc::new(("https://example.com/doc", "Ada"));
+        let chunks = Utf8Splitter::new("abcde", 2, 0, true).split_with_meta(Arc::clone(&meta));
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].text, "e");
+        assert_eq!(chunks[2].range, 4..5);
+        assert!(chunks.iter().all(|c| Arc::ptr_eq(&c.meta, &meta)));
+        assert_eq!(Arc::strong_count(&meta), 4);
+    }
+
+    #[test]
+    fn split_into_reuses_buffer() {
+        let mut buf = Vec::with_capacity(16);
+        buf.push("stale");
+        let ptr = buf.as_ptr();
+        let sp = Utf8Splitter::new("ab😀cdé", 2, 1, true).trim(TrimPolicy::Both);
+        sp.split_into(&mut buf);
+        assert_eq!(buf, sp.split());
+        for s in ["the quick brown fox", "jumps over", "the lazy dog 😀 ok"] {
+            utf8_by_chars_borrowed_into(s, 4, 2, true, &mut buf);
+            assert_eq!(buf, utf8_by_chars_borrowed(s, 4, 2, true));
+        }
+        // No reallocation: the caller's buffer was big enough throughout.
+        assert_eq!((buf.as_ptr(), buf.capacity()), (ptr, 16));
+    }
+
+    #[test]
+    fn collect_chunks_into_any_target() {
+        use std::collections::{BTreeSet, HashSet, VecDeque};
+        let sp = Utf8Splitter::new("abab😀ab", 2, 0, true);
+        let set: HashSet<&str> = sp.collect_chunks();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains("😀a"));
+        let owned: BTreeSet<String> = sp.collect_owned();
+        assert_eq!(
+            owned.into_iter().collect::<Vec<_>>(),
+            vec!["ab", "b", "😀a"]
+        );
+        let deque: VecDeque<&str> = sp.collect_chunks();
+        assert_eq!(Vec::from(deque), sp.split());
+        let trimmed = Utf8Splitter::new("a \u{3000} b\r\nc", 2, 1, true)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true)
+            .normalize_line_endings(true);
+        let v: Vec<String> = trimmed.collect_owned();
+        assert_eq!(v, trimmed.out());
+        assert_eq!(v.len(), trimmed.split_indices().len());
+    }
+
+    #[test]
+    fn to_serializable_is_owned_and_marks_tail() {
+        let records = {
+            let text = String::from("añbcdéf");
+            Utf8Splitter::new(&text, 3, 0, true).to_serializable("doc")
+        };
+        let texts: Vec<_> = records.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, vec!["añb", "cdé", "f"]);
+        let tails: Vec<_> = records.iter().map(|r| r.is_tail).collect();
+        assert_eq!(tails, vec![false, false, true]);
+        assert_eq!((records[1].start, records[1].end), (4, 8));
+        assert_eq!(records[2].index, 2);
+        assert_eq!(
+            records[0].chunk_id,
+            format!("{:032x}", chunk_id("doc", "añb"))
+        );
+        let exact = Utf8Splitter::new("abcdef", 3, 0, true).to_serializable("doc");
+        assert!(exact.iter().all(|r| !r.is_tail));
+    }
+
+    #[test]
+    fn out_cow_borrows_until_modified() {
+        let s = "abcdéfg";
+        let mut v = Utf8Splitter::new(s, 3, 0, true).out_cow();
+        assert!(v.iter().all(|c| matches!(c, Cow::Borrowed(_))));
+        v[1].to_mut().make_ascii_uppercase();
+        assert_eq!(v, vec!["abc", "DéF", "g"]);
+        assert!(matches!(v[1], Cow::Owned(_)));
+        assert!(matches!(
+            (&v[0], &v[2]),
+            (Cow::Borrowed(_), Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn split_prefer_newlines_falls_back_to_spaces() {
+        let s = "first line\nsecond line\na very long line without breaks here\nend";
+        let v = Utf8Splitter::split_prefer_newlines(s, 24, 0);
+        assert_eq!(
+            v,
+            vec![
+                "first line\nsecond line\n",
+                "a very long line ",
+                "without breaks here\nend",
+            ]
+        );
+        assert_eq!(v.concat(), s);
+        let hard = Utf8Splitter::split_prefer_newlines("abcdéfghij\nk", 4, 0);
+        assert_eq!(hard, vec!["abcd", "éfgh", "ij\nk"]);
+        let overlapped = Utf8Splitter::split_prefer_newlines("aa bb\ncc dd", 7, 2);
+        assert_eq!(overlapped, vec!["aa bb\n", "b\ncc dd"]);
+    }
+
+    #[test]
+    fn zero_width_windows_are_empty_slices() {
+        let s = "aé😀";
+        let sp = Utf8Splitter::new(s, 0, 1, true);
+        assert_eq!(sp.split(), vec![""; 4]);
+        assert_eq!(sp.split_indices(), vec![0..0, 1..1, 3..3, 7..7]);
+        assert_eq!(sp.chunk_count(), 4);
+        assert_eq!(utf8_by_chars_borrowed(s, 0, 2, false), vec!["", ""]);
+        assert_eq!((sp.nth(3), sp.nth(4)), (Some(""), None));
+        assert_eq!(sp.try_split(), Err(SplitError::ZeroWindow));
+
+        let stuck = Utf8Splitter::new(s, 0, 0, true);
+        assert!(stuck.split().is_empty() && stuck.out().is_empty());
+        assert_eq!((stuck.chunk_count(), stuck.nth(0)), (0, None));
+        assert_eq!(stuck.try_split(), Err(SplitError::ZeroWindowAndStep));
+    }
+
+    #[test]
+    fn chunk_front_door() {
+        let s = "añ😀bcdéfg";
+        assert_eq!(chunk(s, 4, 1), Utf8Splitter::new(s, 4, 3, true).split());
+        assert_eq!(chunk(s, 4, 0), vec!["añ😀b", "cdéf", "g"]);
+        assert!(chunk("", 4, 1).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "overlap")]
+    fn chunk_rejects_overlap_at_least_size() {
+        chunk("abc", 2, 2);
+    }
+
+    #[test]
+    fn split_min_size_backfills_short_chunks() {
+        let s = "abcdéfghijk";
+        assert_eq!(
+            Utf8Splitter::new(s, 4, 3, true).split(),
+            vec!["abcd", "défg", "ghij", "jk"]
+        );
+        assert_eq!(
+            Utf8Splitter::split_min_size(s, 4, 3, 1),
+            vec!["abcd", "défg", "ghij", "ijk"]
+        );
+        let min_above_target = Utf8Splitter::split_min_size(s, 4, 6, 0);
+        assert_eq!(min_above_target, vec!["abcd", "cdéfgh", "fghijk"]);
+        assert_eq!(Utf8Splitter::split_min_size("ab", 4, 3, 0), vec!["ab"]);
+        assert!(Utf8Splitter::split_min_size("", 4, 3, 0).is_empty());
+    }
+
+    #[test]
+    fn par_out_matches_out() {
+        let mut next = crate::splitter::utils::lcg(0x9e37_79b9);
+        let alphabet: Vec<char> = "ab é😀\n".chars().collect();
+        let doc: String = (0..200_000)
+            .map(|_| alphabet[next(alphabet.len() as u64)])
+            .collect();
+        for (n, step) in [(1000, 800), (37, 0), (5, 2)] {
+            let sp = Utf8Splitter::new(&doc, n, step, true).trim(TrimPolicy::Both);
+            assert_eq!(sp.par_out(), sp.out(), "n={n} step={step}");
+        }
+        assert!(Utf8Splitter::new("", 3, 0, true).par_out().is_empty());
+    }
+
+    #[test]
+    fn split_shared_outlives_splitter() {
+        let chunks = {
+            let src: Arc<str> = Arc::from("abcdéfgh");
+            Utf8Splitter::new(&src, 4, 2, false).split_shared(src.clone())
+        };
+        assert_eq!(chunks, vec!["abcd", "cdéf", "éfgh"]);
+        let source = chunks[0].source();
+        assert!(chunks.iter().all(|c| Arc::ptr_eq(c.source(), source)));
+        assert_eq!(Arc::strong_count(source), chunks.len());
+        assert_eq!(chunks[2].range(), 4..9);
+    }
+
+    #[test]
+    fn split_filtered_keeps_original_indices() {
+        let s = "abc      def   \n\n    ghi";
+        let sp = Utf8Splitter::new(s, 3, 0, true).trim(TrimPolicy::Both);
+        assert_eq!(
+            sp.split_filtered(|c| !c.is_empty()),
+            vec![(0, "abc"), (3, "def"), (7, "ghi")]
+        );
+        assert_eq!(sp.split_filtered(|_| true).len(), sp.chunk_count());
+        assert!(sp.split_filtered(|c| c.len() > 3).is_empty());
+    }
+
+    #[test]
+    fn ansi_aware_counts_visible_characters_only() {
+        let s = "\x1b[31mred\x1b[0m plain";
+        let sp = Utf8Splitter::new(s, 3, 0, true).ansi_aware(true);
+        assert_eq!(sp.split(), vec!["\x1b[31mred\x1b[0m", " pl", "ain"]);
+        assert_eq!(sp.chunk_count(), 3);
+        assert_eq!(sp.split().concat(), s);
+
+        let s = "ab\x1b[1;32mcd";
+        assert_eq!(
+            Utf8Splitter::new(s, 2, 0, true).ansi_aware(true).split(),
+            vec!["ab\x1b[1;32m", "cd"]
+        );
+        assert_eq!(
+            Utf8Splitter::new(s, 3, 0, true).split(),
+            vec!["ab\x1b", "[1;", "32m", "cd"]
+        );
+        let osc = "a\x1b]0;títle\x07b\x1b]8;;x\x1b\\c";
+        assert_eq!(
+            Utf8Splitter::new(osc, 1, 0, true).ansi_aware(true).split(),
+            vec!["a\x1b]0;títle\x07", "b\x1b]8;;x\x1b\\", "c"]
+        );
+    }
+
+    #[test]
+    fn ansi_aware_unterminated_and_overlapping() {
+        let s = "abc\x1b[31";
+        assert_eq!(
+            Utf8Splitter::new(s, 2, 0, true).ansi_aware(true).split(),
+            vec!["ab", "c\x1b[31"]
+        );
+        let s = "a\x1b[1mb\x1b[0mcd\x1b[4me";
+        let chunks = Utf8Splitter::new(s, 3, 1, false).ansi_aware(true).split();
+        assert_eq!(
+            chunks,
+            vec!["a\x1b[1mb\x1b[0mc", "b\x1b[0mcd\x1b[4m", "cd\x1b[4me"]
+        );
+        let crlf = "\u{FEFF}\x1b[1ma\r\nb";
+        assert_eq!(
+            Utf8Splitter::new(crlf, 2, 0, true)
+                .normalize_line_endings(true)
+                .ansi_aware(true)
+                .split(),
+            vec!["\x1b[1ma\r\n", "b"]
+        );
+        assert!(Utf8Splitter::new("\x1b[0m", 2, 0, true)
+            .ansi_aware(true)
+            .split()
+            .is_empty());
+    }
+
+    fn text_from(alphabet: &'static [char], max_len: usize) -> impl Strategy<Value = String> {
+        prop::collection::vec(prop::sample::select(alphabet), 0..max_len)
+            .prop_map(|chars| chars.into_iter().collect())
+    }
+
+    proptest! {
+        #[test]
+        fn owned_and_borrowed_outputs_agree(
+            text in text_from(
+                &['a', 'b', ' ', '\n', '\r', 'é', '😀', '\u{301}', '\u{1b}', '[', 'm'],
+                40,
+            ),
+            n in 0..8usize,
+            step in 0..10usize,
+            tail in any_tail(),
+            trim in prop::sample::select(vec![TrimPolicy::None, TrimPolicy::Both]),
+            options in prop::array::uniform4(any::<bool>()),
+        ) {
+            let [skip_empty, normalize, keep_clusters, ansi] = options;
+            let sp = Utf8Splitter::with_tail(&text, n, step, tail)
+                .trim(trim)
+                .skip_empty(skip_empty)
+                .normalize_line_endings(normalize)
+                .avoid_breaking_clusters(keep_clusters)
+                .ansi_aware(ansi);
+            let borrowed: Vec<String> = sp.split().iter().map(ToString::to_string).collect();
+            prop_assert_eq!(sp.out(), borrowed.clone());
+            prop_assert_eq!(sp.par_out(), borrowed.clone());
+            prop_assert_eq!(sp.collect_owned::<Vec<_>>(), borrowed.clone());
+            prop_assert_eq!(sp.chunk_count(), borrowed.len());
+        }
+
+        #[test]
+        fn non_overlapping_windows_reproduce_covered_input(
+            text in text_from(&['a', ' ', '\n', 'é', '😀', '\u{301}'], 40),
+            n in 1..8usize,
+            explicit_step: bool,
+            tail in prop::sample::select(vec![Tail::Drop, Tail::Keep, Tail::Exact]),
+        ) {
+            let step = if explicit_step { n } else { 0 };
+            let sp = Utf8Splitter::with_tail(&text, n, step, tail);
+            let covered = sp.split_indices().last().map_or(0, |r| r.end);
+            prop_assert_eq!(sp.split().concat(), &text[..covered]);
+            if tail == Tail::Keep {
+                prop_assert_eq!(covered, text.len());
+            }
+        }
+    }
+
+    #[test]
+    fn stats_for_known_config() {
+        // abcd, défg, ghij, j
+        let sp = Utf8Splitter::new("abcdéfghij", 4, 3, true);
+        assert_eq!(sp.overlaps(), vec![1, 1, 1]);
+        assert_eq!(
+            sp.stats(),
+            SplitStats {
+                count: 4,
+                min_chars: 1,
+                max_chars: 4,
+                mean_chars: 3.25,
+                total_overlap_chars: 3,
+            }
+        );
+        assert_eq!(
+            Utf8Splitter::new("abcdefg", 2, 3, true).overlaps(),
+            vec![0, 0]
+        );
+        assert_eq!(Utf8Splitter::new("", 2, 0, true).stats().count, 0);
+    }
+
+    #[test]
+    fn split_unique_keeps_first_occurrences() {
+        let sp = Utf8Splitter::new("ababab", 2, 1, false);
+        assert_eq!(sp.split(), vec!["ab", "ba", "ab", "ba", "ab"]);
+        assert_eq!(sp.split_unique(), vec!["ab", "ba"]);
+        let sp = Utf8Splitter::new("xyzabcxyzxyz", 3, 0, true);
+        assert_eq!(sp.split_unique(), vec!["xyz", "abc"]);
+    }
+
+    #[test]
+    fn split_many_matches_splitting_each_document() {
+        let docs: Vec<String> = (0..50)
+            .map(|i| "añb 😀 ".repeat(i % 7) + &i.to_string())
+            .collect();
+        let refs: Vec<&str> = docs.iter().map(String::as_str).collect();
+        let config = SplitterConfig::new(5, 3, true).trim(TrimPolicy::Both);
+        let chunks = split_many(&refs, &config);
+        assert_eq!(chunks.len(), docs.len());
+        for (doc, got) in docs.iter().zip(&chunks) {
+            let expected = Utf8Splitter::new(doc, 5, 3, true)
+                .trim(TrimPolicy::Both)
+                .split();
+            assert_eq!(got, &expected);
+        }
+        assert!(split_many(&[], &config).is_empty());
+    }
+
+    #[test]
+    fn config_round_trips_through_a_splitter() {
+        let config = SplitterConfig::with_tail(3, 2, Tail::PadBackward)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true)
+            .normalize_line_endings(true)
+            .avoid_breaking_clusters(true)
+            .ansi_aware(true);
+        assert_eq!(config.splitter("abc").config(), config);
+        let s = "a\r\nb  c\u{301}d";
+        let direct = Utf8Splitter::with_tail(s, 3, 2, Tail::PadBackward)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true)
+            .normalize_line_endings(true)
+            .avoid_breaking_clusters(true)
+            .ansi_aware(true);
+        assert_eq!(config.splitter(s).split(), direct.split());
+    }
+
+    #[test]
+    fn checked_new_reports_warnings() {
+        let (sp, warnings) = Utf8Splitter::checked_new("añb😀cdéfg", 3, 4, false);
+        assert_eq!(
+            warnings,
+            vec![
+                SplitWarning::StepSkipsElements { gap: 1 },
+                SplitWarning::TailDropped { len: 2 }
+            ]
+        );
+        assert_eq!(sp.split(), vec!["añb", "cdé"]);
+        assert_eq!(
+            warnings[1].to_string(),
+            "the last 2 elements are dropped with the tail"
+        );
+        let (_, warnings) = Utf8Splitter::checked_new("añb😀cdé", 3, 2, true);
+        assert!(warnings.is_empty());
+    }
+}
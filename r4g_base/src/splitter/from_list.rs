@@ -1,53 +1,498 @@
-use crate::splitter::utils::sliding_windows;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::ops::{ControlFlow, Deref, Range};
+use std::sync::Arc;
+
+use crate::splitter::chunk::SharedSlice;
+use crate::splitter::utils::{
+    nth_window, par_map_ordered, sliding_windows, split_into_k, window_count, SeparatorPolicy,
+    SplitError, StableHasher, Tail, TailPolicy,
+};
 
 /// Splits a slice into sub-slices of length `n`, with step and optional tail, borrowing from the input.
 ///
 /// - `input`: The input slice.
 /// - `n`: Number of elements per window.
 /// - `step`: Step between window starts (`0` means `step = n`).
-/// - `keep_tail`: If true, includes a final sub-slice for any remaining elements at the end.
+/// - `tail`: How to handle remaining elements at the end (`bool`, `Tail` or `TailPolicy`).
 ///
 /// Returns a vector of sub-slices (`&[T]`) borrowing from the input slice.
 pub fn slice_by_windows_borrowed<T>(
     input: &[T],
     n: usize,
     step: usize,
-    keep_tail: bool,
+    tail: impl Into<TailPolicy>,
 ) -> Vec<&[T]> {
     let len = input.len();
+    let tail = tail.into();
 
-    let mut out = Vec::new();
-    for r in sliding_windows(len, n, step, keep_tail) {
+    let mut out = Vec::with_capacity(window_count(len, n, step, tail));
+    for r in sliding_windows(len, n, step, tail) {
         out.push(&input[r]);
     }
     out
 }
 
 
+/// Like `slice_by_windows_borrowed`, but clears and refills `out` instead of allocating a new
+/// vector.
+pub fn slice_by_windows_borrowed_into<'a, T>(
+    input: &'a [T],
+    n: usize,
+    step: usize,
+    tail: impl Into<TailPolicy>,
+    out: &mut Vec<&'a [T]>,
+) {
+    let tail = tail.into();
+    out.clear();
+    out.reserve(window_count(input.len(), n, step, tail));
+    out.extend(sliding_windows(input.len(), n, step, tail).map(|r| &input[r]));
+}
+
+/// Corresponding windows of two slices, from `zip_split`.
+pub type ZippedWindows<'a, 'b, A, B> = Vec<(&'a [A], &'b [B])>;
+
+/// Splits two slices of equal length with the same windows, so element `i` of `a` and element
+/// `i` of `b` always land in corresponding windows (e.g. texts and their precomputed scores).
+///
+/// - `n`, `step`, `tail`: As for `slice_by_windows_borrowed`.
+///
+/// Returns `SplitError::LengthMismatch` if `a` and `b` differ in length.
+pub fn zip_split<'a, 'b, A, B>(
+    a: &'a [A],
+    b: &'b [B],
+    n: usize,
+    step: usize,
+    tail: impl Into<TailPolicy>,
+) -> Result<ZippedWindows<'a, 'b, A, B>, SplitError> {
+    if a.len() != b.len() {
+        return Err(SplitError::LengthMismatch {
+            left: a.len(),
+            right: b.len(),
+        });
+    }
+    Ok(sliding_windows(a.len(), n, step, tail)
+        .map(|r| (&a[r.clone()], &b[r]))
+        .collect())
+}
+
+/// Splits `input` at every element for which `is_boundary` returns `true`, handling the boundary
+/// elements according to `policy`, and returns borrowed pieces.
+///
+/// - `allow_empty`: Keep the empty pieces left by consecutive boundaries and by boundaries at
+///   either end (for `Drop`, as with `slice::split`); otherwise they are omitted.
+///
+/// With any policy but `Drop`, the pieces (empty ones included or not) concatenate back to
+/// `input`. An empty `input` yields no pieces.
+pub fn split_slice_by<'a, T>(
+    input: &'a [T],
+    is_boundary: impl Fn(&T) -> bool,
+    policy: SeparatorPolicy,
+    allow_empty: bool,
+) -> Vec<&'a [T]> {
+    let mut out = Vec::new();
+    if input.is_empty() {
+        return out;
+    }
+    let mut push = |piece: &'a [T]| {
+        if allow_empty || !piece.is_empty() {
+            out.push(piece);
+        }
+    };
+    let mut start = 0usize;
+    for (i, item) in input.iter().enumerate() {
+        if !is_boundary(item) {
+            continue;
+        }
+        match policy {
+            SeparatorPolicy::Drop => {
+                push(&input[start..i]);
+                start = i + 1;
+            }
+            SeparatorPolicy::AttachToPrevious => {
+                push(&input[start..=i]);
+                start = i + 1;
+            }
+            SeparatorPolicy::AttachToNext => {
+                push(&input[start..i]);
+                start = i;
+            }
+            SeparatorPolicy::Isolate => {
+                push(&input[start..i]);
+                push(&input[i..=i]);
+                start = i + 1;
+            }
+        }
+    }
+    push(&input[start..]);
+    out
+}
+
+/// Windows each of `pieces` on its own, e.g. the pieces of `split_slice_by`, so no window
+/// spans two pieces.
+///
+/// - `n`, `step`, `tail`: As for `slice_by_windows_borrowed`, applied to every piece.
+pub fn window_pieces<'a, T>(
+    pieces: &[&'a [T]],
+    n: usize,
+    step: usize,
+    tail: impl Into<TailPolicy>,
+) -> Vec<Vec<&'a [T]>> {
+    let tail = tail.into();
+    pieces
+        .iter()
+        .map(|piece| slice_by_windows_borrowed(piece, n, step, tail))
+        .collect()
+}
+
 /// A splitter for dividing a list (slice) into sub-slices of specified length by element count.
 ///
 /// - `input`: The input list (slice) to split.
 /// - `n`: The number of elements per sub-slice.
 /// - `step`: Step between window starts (`0` means no overlap).
-/// - `keep_tail`: If true, includes a final sub-slice for any remaining elements at the end.
+/// - `tail`: How to handle any remaining elements at the end (see `Tail`).
 ///
 /// Provides methods to get borrowed sub-slices (`split`) or owned sub-vectors (`out`).
+///
+/// An `n` of `0` yields an empty sub-slice at every `step`-th position, `input.len()` included,
+/// and nothing when `step` is also `0`; `try_split` rejects both.
 pub struct SliceSplitter<'a, T> {
     input: &'a [T],
     n: usize,
     step: usize,
-    keep_tail: bool,
+    tail: TailPolicy,
 }
 
 impl<'a, T> SliceSplitter<'a, T> {
     /// Create a new SliceSplitter.
     pub fn new(input: &'a [T], n: usize, step: usize, keep_tail: bool) -> Self {
-        Self { input, n, step, keep_tail }
+        Self::with_tail(input, n, step, keep_tail.into())
+    }
+
+    /// Create a new SliceSplitter with an explicit tail policy.
+    pub fn with_tail(input: &'a [T], n: usize, step: usize, tail: Tail) -> Self {
+        Self { input, n, step, tail: tail.into() }
+    }
+
+    /// Handle the trailing elements with `policy`, which can also depend on how many there are
+    /// (e.g. `TailPolicy::MergeBelow(3)`). Applies to `split`, `iter` and every other output.
+    pub fn tail_policy(mut self, policy: TailPolicy) -> Self {
+        self.tail = policy;
+        self
     }
 
     /// Return a vector of borrowed sub-slices.
     pub fn split(&self) -> Vec<&'a [T]> {
-        slice_by_windows_borrowed(self.input, self.n, self.step, self.keep_tail)
+        self.iter().collect()
+    }
+
+    /// Iterate over the sub-slices lazily; nothing is computed ahead of the window being
+    /// yielded.
+    pub fn iter(&self) -> SliceWindows<'a, T> {
+        SliceWindows {
+            input: self.input,
+            n: self.n,
+            step: self.step,
+            tail: self.tail,
+            front: 0,
+            back: self.chunk_count(),
+        }
+    }
+
+    /// Like `split`, with each sub-slice tagged with its index, its element range in the input
+    /// and whether it is the tail window (see `SliceWindow`).
+    pub fn split_indexed(&self) -> Vec<SliceWindow<'a, T>> {
+        self.iter_indexed().collect()
+    }
+
+    /// Like `iter`, yielding the `SliceWindow`s of `split_indexed` lazily.
+    pub fn iter_indexed(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = SliceWindow<'a, T>> + ExactSizeIterator + 'a {
+        let (input, n, step, tail) = (self.input, self.n, self.step, self.tail);
+        let full = window_count(input.len(), n, step, Tail::Drop);
+        // Every index below the window count has a window, so the fallback is never used.
+        (0..self.chunk_count()).map(move |index| {
+            let range = nth_window(input.len(), n, step, tail, index).unwrap_or(0..0);
+            SliceWindow {
+                index,
+                data: &input[range.clone()],
+                range,
+                is_tail: index >= full,
+            }
+        })
+    }
+
+    /// Collect the sub-slices into any `FromIterator` target without an intermediate vector.
+    pub fn collect_chunks<C: FromIterator<&'a [T]>>(&self) -> C {
+        self.iter().collect()
+    }
+
+    /// Like `collect_chunks`, with each sub-slice copied into an owned `Vec<T>`.
+    pub fn collect_owned<C: FromIterator<Vec<T>>>(&self) -> C
+    where
+        T: Clone,
+    {
+        let input = self.input;
+        sliding_windows(input.len(), self.n, self.step, self.tail)
+            .map(|r| input[r].to_vec())
+            .collect()
+    }
+
+    /// Group adjacent elements with equal `key` into runs, then window each run on its own with
+    /// this splitter's settings, so no window mixes two keys. Returns each run's key with its
+    /// windows, in input order.
+    ///
+    /// A run shorter than `n` is handled by the tail policy; a run whose windows are all dropped
+    /// still appears, with no windows. Non-adjacent runs with equal keys stay separate.
+    pub fn grouped_by<K: PartialEq>(&self, key: impl Fn(&T) -> K) -> Vec<(K, Vec<&'a [T]>)> {
+        Self::group_runs(self.input, key)
+            .into_iter()
+            .map(|(k, run)| (k, self.window_run(run)))
+            .collect()
+    }
+
+    fn window_run(&self, run: &'a [T]) -> Vec<&'a [T]> {
+        slice_by_windows_borrowed(run, self.n, self.step, self.tail)
+    }
+
+    /// Return the element range of each sub-slice.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        sliding_windows(self.input.len(), self.n, self.step, self.tail).collect()
+    }
+
+    /// Like `split`, but clears and refills `out` instead of allocating a new vector.
+    pub fn split_into(&self, out: &mut Vec<&'a [T]>) {
+        slice_by_windows_borrowed_into(self.input, self.n, self.step, self.tail, out);
+    }
+
+    /// Like `split`, but rejects a zero window size (and a zero size with a zero step) instead of
+    /// returning empty sub-slices or nothing.
+    pub fn try_split(&self) -> Result<Vec<&'a [T]>, SplitError> {
+        SplitError::check_window(self.n, self.step)?;
+        Ok(self.split())
+    }
+
+    /// Return the number of sub-slices `split` produces, computed from the input length alone.
+    pub fn chunk_count(&self) -> usize {
+        window_count(self.input.len(), self.n, self.step, self.tail)
+    }
+
+    /// Same as `chunk_count`.
+    pub fn count(&self) -> usize {
+        self.chunk_count()
+    }
+
+    /// Return the `i`-th sub-slice by indexing, without computing the others.
+    pub fn nth(&self, i: usize) -> Option<&'a [T]> {
+        nth_window(self.input.len(), self.n, self.step, self.tail, i).map(|r| &self.input[r])
+    }
+
+    /// Packs consecutive elements into windows whose summed `cost` stays within `budget`, e.g.
+    /// sentences weighed by their length in characters. `n`, `step` and `tail` are not used.
+    ///
+    /// A window is closed when the next element would take it over `budget`; an element that
+    /// costs more than `budget` on its own still becomes a window of its own. Every element is in
+    /// exactly one window.
+    pub fn split_by_weight(&self, budget: usize, cost: impl Fn(&T) -> usize) -> Vec<&'a [T]> {
+        self.split_by_weight_overlapping(budget, 0, cost)
+    }
+
+    /// Like `split_by_weight`, with trailing elements worth up to `overlap_weight` repeated at
+    /// the start of the next window.
+    ///
+    /// The overlap is shrunk where it would leave no room for the element that closed the
+    /// previous window, so every window gets at least one new element.
+    pub fn split_by_weight_overlapping(
+        &self,
+        budget: usize,
+        overlap_weight: usize,
+        cost: impl Fn(&T) -> usize,
+    ) -> Vec<&'a [T]> {
+        let input = self.input;
+        let costs: Vec<usize> = input.iter().map(cost).collect();
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        while start < input.len() {
+            let mut end = start;
+            let mut total = 0usize;
+            while end < input.len() && (end == start || total + costs[end] <= budget) {
+                total += costs[end];
+                end += 1;
+            }
+            out.push(&input[start..end]);
+            if end == input.len() {
+                break;
+            }
+
+            let mut next = end;
+            let mut repeated = 0usize;
+            while next > start + 1
+                && repeated + costs[next - 1] <= overlap_weight
+                && repeated + costs[next - 1] + costs[end] <= budget
+            {
+                repeated += costs[next - 1];
+                next -= 1;
+            }
+            start = next;
+        }
+        out
+    }
+
+    /// Divides `input` into `k` contiguous parts whose lengths differ by at most one, e.g. to
+    /// spread work over `k` threads; the parts concatenate to `input`.
+    ///
+    /// With `k > input.len()` there are only `input.len()` parts, one element each; no part is
+    /// empty. `k = 0` is rejected with `SplitError::ZeroParts`.
+    pub fn split_slice_into_k(input: &'a [T], k: usize) -> Result<Vec<&'a [T]>, SplitError> {
+        if k == 0 {
+            return Err(SplitError::ZeroParts);
+        }
+        Ok(split_into_k(input.len(), k)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect())
+    }
+
+    /// Split `input` into maximal runs of consecutive elements with equal keys, in order.
+    ///
+    /// Each run is returned as a borrowed sub-slice together with the key of its first element.
+    /// Equal keys that are not adjacent start separate runs; nothing is reordered.
+    pub fn group_runs<K, F>(input: &'a [T], key: F) -> Vec<(K, &'a [T])>
+    where
+        K: PartialEq,
+        F: Fn(&T) -> K,
+    {
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        let mut current: Option<K> = None;
+        for (i, item) in input.iter().enumerate() {
+            let k = key(item);
+            match current.take() {
+                Some(prev) if prev == k => current = Some(prev),
+                Some(prev) => {
+                    out.push((prev, &input[start..i]));
+                    start = i;
+                    current = Some(k);
+                }
+                None => current = Some(k),
+            }
+        }
+        if let Some(prev) = current {
+            out.push((prev, &input[start..]));
+        }
+        out
+    }
+
+    /// Return each sub-slice paired with its hash under `StableHasher`, the FNV-1a used by
+    /// `hash_chunk`. Stability across platforms depends on the `Hash` impl of `T` (see
+    /// `StableHasher`).
+    ///
+    /// The input is walked once: each element is fed to the hasher of every window still open
+    /// over it, giving the same hash as `Hash` on the sub-slice.
+    pub fn split_hashed(&self) -> Vec<(&'a [T], u64)>
+    where
+        T: Hash,
+    {
+        let input = self.input;
+        let mut windows = sliding_windows(input.len(), self.n, self.step, self.tail).peekable();
+        // Window starts increase and ends never decrease, so windows close in the order they open.
+        let mut open: VecDeque<(Range<usize>, StableHasher)> = VecDeque::new();
+        let mut out = Vec::with_capacity(self.count());
+        for k in 0..=input.len() {
+            while let Some(r) = windows.next_if(|r| r.start <= k) {
+                let mut h = StableHasher::default();
+                // The length prefix `Hash for [T]` writes before the elements.
+                h.write_usize(r.len());
+                open.push_back((r, h));
+            }
+            while open.front().is_some_and(|(r, _)| r.end <= k) {
+                let (r, h) = open.pop_front().unwrap();
+                out.push((&input[r], h.finish()));
+            }
+            if let Some(item) = input.get(k) {
+                for (_, h) in &mut open {
+                    item.hash(h);
+                }
+            }
+        }
+        out
+    }
+
+    /// Call `f(index, window)` for each sub-slice in order, stopping as soon as `f` returns
+    /// `ControlFlow::Break`. No vector of windows is built.
+    pub fn for_each_window(&self, mut f: impl FnMut(usize, &'a [T]) -> ControlFlow<()>) {
+        let windows = sliding_windows(self.input.len(), self.n, self.step, self.tail);
+        for (k, r) in windows.enumerate() {
+            if f(k, &self.input[r]).is_break() {
+                return;
+            }
+        }
+    }
+
+    /// Apply `f` to each sub-slice lazily, yielding the results in order. Windows are computed on
+    /// demand, so nothing is collected unless the caller collects.
+    pub fn map<B, F>(self, f: F) -> impl Iterator<Item = B> + 'a
+    where
+        F: Fn(&'a [T]) -> B + 'a,
+    {
+        let input = self.input;
+        sliding_windows(input.len(), self.n, self.step, self.tail).map(move |r| f(&input[r]))
+    }
+
+    /// Return windows that all share `source` instead of copying their elements, so they can
+    /// outlive the input and cross threads (for `T: Send + Sync`). Each window costs a reference
+    /// count increment rather than the `to_vec` of `out`; see `SharedSlice`.
+    ///
+    /// The windows are laid out over `source` with this splitter's settings; `source` is
+    /// normally the data the splitter was built over.
+    pub fn split_shared(&self, source: Arc<[T]>) -> Vec<SharedSlice<T>> {
+        sliding_windows(source.len(), self.n, self.step, self.tail)
+            .map(|r| SharedSlice::new(Arc::clone(&source), r))
+            .collect()
+    }
+
+    /// Like `out`, with the sub-slices copied in parallel (see `par_map`), keeping the order of
+    /// `out`.
+    pub fn par_out(&self) -> Vec<Vec<T>>
+    where
+        T: Clone + Send + Sync,
+    {
+        self.par_map(<[T]>::to_vec)
+    }
+
+    /// Apply `f` to each sub-slice on the rayon pool, returning the results in window order.
+    /// Without the `rayon` feature the windows are mapped sequentially. Only the window ranges are
+    /// computed up front; no sub-slice is collected or copied.
+    ///
+    /// `self.map(f).collect::<Vec<_>>()` gives the same result sequentially.
+    pub fn par_map<U, F>(&self, f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&[T]) -> U + Sync,
+    {
+        let input = self.input;
+        par_map_ordered(&self.split_indices(), |r| f(&input[r.clone()]))
+    }
+
+    /// Call `f` on each sub-slice in parallel, in no particular order; see `par_map`.
+    pub fn par_for_each<F>(&self, f: F)
+    where
+        T: Sync,
+        F: Fn(&[T]) + Sync,
+    {
+        self.par_map(f);
+    }
+
+    /// Iterate over owned copies of the sub-slices lazily, one window at a time, without
+    /// collecting the borrowed sub-slices first.
+    pub fn out_iter(&self) -> impl DoubleEndedIterator<Item = Vec<T>> + ExactSizeIterator + 'a
+    where
+        T: Clone,
+    {
+        self.iter().map(<[T]>::to_vec)
     }
 
     /// Return a vector of owned sub-vectors.
@@ -55,18 +500,109 @@ impl<'a, T> SliceSplitter<'a, T> {
     where
         T: Clone,
     {
-        self.split().into_iter().map(|s| s.to_vec()).collect()
+        let mut out = Vec::with_capacity(self.chunk_count());
+        out.extend(self.out_iter());
+        out
     }
 }
+
+/// A sub-slice from `SliceSplitter::split_indexed`, with where it came from. Derefs to `data`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceWindow<'a, T> {
+    /// Position of the window among the windows of the splitter.
+    pub index: usize,
+    /// Element range of `data` within the input.
+    pub range: Range<usize>,
+    /// Whether this is the partial window kept at the end by the tail policy.
+    pub is_tail: bool,
+    /// The window's elements, borrowed from the input.
+    pub data: &'a [T],
+}
+
+impl<T> Deref for SliceWindow<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<'a, T> IntoIterator for &SliceSplitter<'a, T> {
+    type Item = &'a [T];
+    type IntoIter = SliceWindows<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Lazy iterator over the sub-slices of a `SliceSplitter`, from `SliceSplitter::iter`.
+///
+/// Each window is located arithmetically from its index (see `nth_window`), so the iterator
+/// knows its exact length and can be advanced or consumed from either end in constant time.
+pub struct SliceWindows<'a, T> {
+    input: &'a [T],
+    n: usize,
+    step: usize,
+    tail: TailPolicy,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> SliceWindows<'a, T> {
+    fn window(&self, index: usize) -> Option<&'a [T]> {
+        nth_window(self.input.len(), self.n, self.step, self.tail, index).map(|r| &self.input[r])
+    }
+}
+
+impl<'a, T> Iterator for SliceWindows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.front += 1;
+        self.window(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, k: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(k).min(self.back);
+        self.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for SliceWindows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.window(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for SliceWindows<'_, T> {}
+
+impl<T> FusedIterator for SliceWindows<'_, T> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::splitter::utils::coverage;
+    use proptest::prelude::*;
 
     #[test]
     fn test_slice_by_windows_borrowed() {
         let data = [1, 2, 3, 4, 5, 6, 7];
         let windows = slice_by_windows_borrowed(&data, 3, 0, true);
         assert_eq!(windows, vec![&[1,2,3][..], &[4,5,6][..], &[7][..]]);
+        assert!(coverage(data.len(), 3, 0, true).is_empty());
     }
     #[test]
     fn test_slice_by_windows_borrowed_strings() {
@@ -81,4 +617,468 @@ mod tests {
             ]
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_slice_splitter_pad_backward() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let windows = SliceSplitter::with_tail(&data, 3, 0, Tail::PadBackward).split();
+        assert_eq!(windows, vec![&[1,2,3][..], &[4,5,6][..], &[5,6,7][..]]);
+        let overlapping = SliceSplitter::with_tail(&data[..6], 4, 2, Tail::PadBackward).split();
+        assert_eq!(overlapping, vec![&[1, 2, 3, 4][..], &[3, 4, 5, 6]]);
+    }
+
+    #[test]
+    fn test_slice_splitter_nth() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let sp = SliceSplitter::new(&data, 3, 2, true);
+        let all = sp.split();
+        for (k, w) in all.iter().enumerate() {
+            assert_eq!(sp.nth(k), Some(*w));
+        }
+        assert_eq!(sp.nth(all.len()), None);
+    }
+
+    #[test]
+    fn test_slice_splitter_chunk_count() {
+        let data: Vec<u32> = (0..23).collect();
+        for len in 0..data.len() {
+            for n in 1..6 {
+                for step in 0..7 {
+                    for tail in [Tail::Drop, Tail::Keep, Tail::PadBackward] {
+                        let sp = SliceSplitter::with_tail(&data[..len], n, step, tail);
+                        assert_eq!(sp.chunk_count(), sp.split().len());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_runs() {
+        let logs = [("db", 1), ("db", 2), ("web", 3), ("db", 4), ("db", 5)];
+        let runs = SliceSplitter::group_runs(&logs, |r| r.0);
+        assert_eq!(
+            runs,
+            vec![
+                ("db", &logs[0..2]),
+                ("web", &logs[2..3]),
+                ("db", &logs[3..5]),
+            ]
+        );
+        let empty: [u8; 0] = [];
+        assert!(SliceSplitter::group_runs(&empty, |b| *b).is_empty());
+    }
+
+    #[test]
+    fn test_slice_split_hashed() {
+        let data = [1u8, 2, 1, 2, 3];
+        let v = SliceSplitter::new(&data, 2, 0, true).split_hashed();
+        assert_eq!(v.len(), 3);
+        assert_eq!(v[0].1, v[1].1);
+        assert_ne!(v[0].1, v[2].1);
+
+        let words = ["a", "bb", "a", "bb", "ccc", "a", "bb"];
+        for (n, step) in [(1, 0), (2, 1), (3, 2), (2, 4), (3, 0)] {
+            for tail in [Tail::Keep, Tail::Drop, Tail::PadBackward] {
+                let sp = SliceSplitter::with_tail(&words, n, step, tail);
+                let v = sp.split_hashed();
+                assert_eq!(v.iter().map(|&(w, _)| w).collect::<Vec<_>>(), sp.split());
+                for (w, h) in v {
+                    let mut expected = StableHasher::default();
+                    w.hash(&mut expected);
+                    assert_eq!(h, expected.finish());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_slice_splitter_map() {
+        let data = [1, 2, 3, 4, 5];
+        let sums: Vec<i32> = SliceSplitter::new(&data, 2, 0, true)
+            .map(|w| w.iter().sum())
+            .collect();
+        assert_eq!(sums, vec![3, 7, 5]);
+    }
+
+    #[test]
+    fn test_for_each_window_breaks_early() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let mut seen = Vec::new();
+        SliceSplitter::new(&data, 3, 0, true).for_each_window(|k, w| {
+            seen.push(w);
+            if k == 1 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(seen, vec![&data[0..3], &data[3..6]]);
+    }
+
+    #[test]
+    fn test_split_into() {
+        let data = [1, 2, 3, 4, 5];
+        let mut buf: Vec<&[i32]> = Vec::with_capacity(4);
+        buf.push(&data[..1]);
+        let ptr = buf.as_ptr();
+        SliceSplitter::new(&data, 2, 0, true).split_into(&mut buf);
+        assert_eq!(buf, vec![&data[0..2], &data[2..4], &data[4..5]]);
+        assert_eq!((buf.as_ptr(), buf.capacity()), (ptr, 4));
+    }
+
+    #[test]
+    fn test_collect_chunks() {
+        use std::collections::{BTreeMap, HashSet};
+        let data = [1, 2, 1, 2, 3];
+        let sp = SliceSplitter::new(&data, 2, 0, true);
+        let set: HashSet<&[i32]> = sp.collect_chunks();
+        assert_eq!(set.len(), 2);
+        assert_eq!(sp.split(), slice_by_windows_borrowed(&data, 2, 0, true));
+        let owned: Vec<Vec<i32>> = sp.collect_owned();
+        let map: BTreeMap<usize, Vec<i32>> = owned.into_iter().enumerate().collect();
+        assert_eq!(map[&2], vec![3]);
+        assert_eq!(sp.out(), vec![vec![1, 2], vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_zero_width_windows() {
+        let data = [1, 2, 3];
+        let empty: &[i32] = &[];
+        let sp = SliceSplitter::new(&data, 0, 1, false);
+        assert_eq!(sp.split(), vec![empty; 4]);
+        assert_eq!(sp.chunk_count(), 4);
+        assert_eq!((sp.nth(3), sp.nth(4)), (Some(empty), None));
+        assert_eq!(sp.try_split(), Err(SplitError::ZeroWindow));
+        let stuck = SliceSplitter::new(&data, 0, 0, true);
+        assert!(stuck.split().is_empty() && stuck.out().is_empty());
+        assert_eq!(stuck.try_split(), Err(SplitError::ZeroWindowAndStep));
+        assert_eq!(
+            SliceSplitter::new(&data, 2, 0, true).try_split(),
+            Ok(vec![&data[..2], &data[2..]])
+        );
+    }
+
+    #[test]
+    fn test_par_out() {
+        let data: Vec<u32> = (0..100_000).collect();
+        for (n, step) in [(100, 60), (7, 0), (3, 5)] {
+            let sp = SliceSplitter::new(&data, n, step, true);
+            assert_eq!(sp.par_out(), sp.out());
+        }
+    }
+
+    fn any_tail() -> impl Strategy<Value = Tail> {
+        prop::sample::select(vec![Tail::Drop, Tail::Keep, Tail::PadBackward, Tail::Exact])
+    }
+
+    proptest! {
+        #[test]
+        fn owned_and_borrowed_outputs_agree(
+            data in prop::collection::vec(any::<u16>(), 0..40),
+            n in 0..8usize,
+            step in 0..10usize,
+            tail in any_tail(),
+        ) {
+            let sp = SliceSplitter::with_tail(&data, n, step, tail);
+            let borrowed: Vec<Vec<u16>> = sp.split().iter().map(|w| w.to_vec()).collect();
+            prop_assert_eq!(sp.out(), borrowed.clone());
+            prop_assert_eq!(sp.par_out(), borrowed.clone());
+            prop_assert_eq!(sp.count(), borrowed.len());
+
+            if n > 0 && (step == 0 || step == n) && tail != Tail::PadBackward {
+                let covered = sp.split_indices().last().map_or(0, |r| r.end);
+                prop_assert_eq!(sp.split().concat(), &data[..covered]);
+            }
+        }
+
+        #[test]
+        fn iter_matches_sliding_windows(
+            len in 0..30usize,
+            n in 0..8usize,
+            step in 0..10usize,
+            tail in any_tail(),
+        ) {
+            let data: Vec<usize> = (0..len).collect();
+            let sp = SliceSplitter::with_tail(&data, n, step, tail);
+            let expected: Vec<&[usize]> = sliding_windows(data.len(), n, step, tail)
+                .map(|r| &data[r])
+                .collect();
+            prop_assert_eq!(sp.split(), expected.clone());
+            let mut reversed: Vec<_> = sp.iter().rev().collect();
+            reversed.reverse();
+            prop_assert_eq!(reversed, expected);
+        }
+    }
+
+    #[test]
+    fn iter_is_lazy_and_exact() {
+        let huge = vec![(); usize::MAX / 2];
+        let mut windows = SliceSplitter::new(&huge, 3, 1, true).iter();
+        assert_eq!(windows.next().map(<[()]>::len), Some(3));
+        assert_eq!(windows.len(), usize::MAX / 2 - 2);
+
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let sp = SliceSplitter::new(&data, 3, 2, true);
+        let mut it = sp.iter();
+        assert_eq!(it.size_hint(), (4, Some(4)));
+        assert_eq!(it.next_back(), Some(&[7][..]));
+        assert_eq!(it.len(), 3);
+        assert_eq!(
+            it.collect::<Vec<_>>(),
+            vec![&[1, 2, 3][..], &[3, 4, 5], &[5, 6, 7]]
+        );
+        assert_eq!((&sp).into_iter().nth(2), Some(&[5, 6, 7][..]));
+        let mut total = 0;
+        for w in &sp {
+            total += w.len();
+        }
+        assert_eq!(total, 10);
+    }
+
+    #[test]
+    fn split_shared_windows_outlive_splitter() {
+        let windows = {
+            let source: Arc<[String]> = (0..5).map(|i| i.to_string()).collect();
+            let sp = SliceSplitter::new(&source, 2, 1, false);
+            let windows = sp.split_shared(source.clone());
+            std::thread::spawn(move || windows).join().unwrap()
+        };
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3], &["3".to_string(), "4".to_string()][..]);
+        assert_eq!(Arc::strong_count(windows[0].source()), 4);
+    }
+
+    #[test]
+    fn split_indexed_tags_windows() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let windows = SliceSplitter::new(&data, 3, 2, true).split_indexed();
+        let indices: Vec<usize> = windows.iter().map(|w| w.index).collect();
+        let ranges: Vec<Range<usize>> = windows.iter().map(|w| w.range.clone()).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert_eq!(ranges, vec![0..3, 2..5, 4..7, 6..7]);
+        assert_eq!(windows.iter().filter(|w| w.is_tail).count(), 1);
+        assert_eq!((&*windows[3], windows[3].is_tail), (&[7][..], true));
+        assert_eq!(windows[1].iter().sum::<i32>(), 12);
+
+        let padded = SliceSplitter::with_tail(&data, 3, 0, Tail::PadBackward);
+        let last = padded.iter_indexed().next_back().unwrap();
+        assert_eq!((last.index, last.range, last.is_tail), (2, 4..7, true));
+        assert!(SliceSplitter::new(&data, 7, 0, true)
+            .iter_indexed()
+            .all(|w| !w.is_tail));
+    }
+
+    #[test]
+    fn zip_split_keeps_pairs_aligned() {
+        let texts = ["a", "b", "c", "d", "e"];
+        let scores = [0.1, 0.2, 0.3, 0.4, 0.5];
+        let pairs = zip_split(&texts, &scores, 2, 1, Tail::Keep).unwrap();
+        assert_eq!(pairs.len(), 5);
+        for ((t, s), r) in pairs.iter().zip(sliding_windows(5, 2, 1, Tail::Keep)) {
+            assert_eq!((*t, *s), (&texts[r.clone()], &scores[r]));
+        }
+        assert_eq!(pairs[3], (&["d", "e"][..], &[0.4, 0.5][..]));
+        assert_eq!(pairs[4], (&["e"][..], &[0.5][..]));
+        assert_eq!(
+            zip_split(&texts, &scores[..4], 2, 0, true),
+            Err(SplitError::LengthMismatch { left: 5, right: 4 })
+        );
+    }
+
+    #[test]
+    fn split_by_weight_packs_within_budget() {
+        let sentences = ["aaaa", "bb", "cccc", "dddddddddddd", "e", "ff"];
+        let sp = SliceSplitter::new(&sentences, 1, 0, true);
+        assert_eq!(
+            sp.split_by_weight(6, |s| s.len()),
+            vec![
+                &["aaaa", "bb"][..],
+                &["cccc"],
+                &["dddddddddddd"],
+                &["e", "ff"]
+            ]
+        );
+        assert!(SliceSplitter::new(&[] as &[&str], 1, 0, true)
+            .split_by_weight(6, |s| s.len())
+            .is_empty());
+    }
+
+    #[test]
+    fn split_by_weight_overlapping_repeats_trailing_elements() {
+        let sp = SliceSplitter::new(&[1, 2, 3, 4, 5, 6], 1, 0, true);
+        let windows = sp.split_by_weight_overlapping(3, 1, |_| 1);
+        assert_eq!(windows, vec![&[1, 2, 3][..], &[3, 4, 5], &[5, 6]]);
+
+        let costs = [5, 5, 10, 2];
+        let sp = SliceSplitter::new(&costs, 1, 0, true);
+        assert_eq!(
+            sp.split_by_weight_overlapping(10, 5, |&c| c),
+            vec![&[5, 5][..], &[10], &[2]]
+        );
+    }
+
+    #[test]
+    fn split_slice_into_k_parts() {
+        let items: Vec<u32> = (0..10_007).collect();
+        let parts = SliceSplitter::split_slice_into_k(&items, 8).unwrap();
+        assert_eq!(parts.len(), 8);
+        let (min, max) = (
+            parts.iter().map(|p| p.len()).min().unwrap(),
+            parts.iter().map(|p| p.len()).max().unwrap(),
+        );
+        assert!(max - min <= 1);
+        assert_eq!(parts.concat(), items);
+
+        let few = [1, 2, 3];
+        assert_eq!(
+            SliceSplitter::split_slice_into_k(&few, 5).unwrap(),
+            vec![&[1][..], &[2], &[3]]
+        );
+        assert_eq!(
+            SliceSplitter::split_slice_into_k(&few, 0),
+            Err(SplitError::ZeroParts)
+        );
+    }
+
+    #[test]
+    fn tail_policy_applies_to_split_and_iter() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let merged =
+            SliceSplitter::new(&data, 3, 0, true).tail_policy(TailPolicy::MergeBelow(2));
+        assert_eq!(merged.split(), vec![&[1, 2, 3][..], &[4, 5, 6, 7]]);
+        assert_eq!(merged.iter().collect::<Vec<_>>(), merged.split());
+        assert_eq!(merged.chunk_count(), 2);
+
+        let short =
+            SliceSplitter::new(&data[..2], 3, 0, false).tail_policy(TailPolicy::MergeBelow(3));
+        assert_eq!(short.split(), vec![&[1, 2][..]]);
+        assert_eq!(short.iter().len(), 1);
+
+        let dropped =
+            SliceSplitter::new(&data, 3, 0, true).tail_policy(TailPolicy::DropBelow(2));
+        assert_eq!(dropped.out(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(dropped.iter().next_back(), Some(&[4, 5, 6][..]));
+    }
+
+    #[test]
+    fn out_iter_streams_owned_windows() {
+        let data: Vec<String> = (0..7).map(|i| i.to_string()).collect();
+        let sp = SliceSplitter::new(&data, 3, 2, true);
+        let mut it = sp.out_iter();
+        assert_eq!(it.len(), 4);
+        assert_eq!(it.next(), Some(vec!["0".to_string(), "1".into(), "2".into()]));
+        assert_eq!(it.next_back(), Some(vec!["6".to_string()]));
+        assert_eq!(it.len(), 2);
+        assert_eq!(sp.out_iter().collect::<Vec<_>>(), sp.out());
+
+        let out = SliceSplitter::new(&data, 2, 0, false).out();
+        assert_eq!(out.len(), 3);
+        assert_eq!(out.capacity(), 3);
+    }
+
+    #[test]
+    fn par_map_matches_sequential_map() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let data: Vec<u32> = (0..5_000).collect();
+        for (n, step, keep) in [(7, 0, true), (64, 16, true), (100, 300, false), (3, 1, true)] {
+            let sp = SliceSplitter::new(&data, n, step, keep);
+            let sum = |w: &[u32]| w.iter().map(|&x| u64::from(x)).sum::<u64>();
+            let total = AtomicUsize::new(0);
+            sp.par_for_each(|w| {
+                total.fetch_add(w.len(), Ordering::Relaxed);
+            });
+            assert_eq!(total.into_inner(), sp.iter().map(<[u32]>::len).sum::<usize>());
+
+            let parallel = sp.par_map(sum);
+            assert_eq!(parallel, sp.map(sum).collect::<Vec<_>>());
+        }
+        assert!(SliceSplitter::new(&data[..0], 4, 0, true).par_map(<[u32]>::len).is_empty());
+    }
+
+    #[test]
+    fn split_slice_by_policies() {
+        let data = [0, 1, 2, 0, 0, 3, 0];
+        let at_zero = |x: &i32| *x == 0;
+        let split = |policy, allow_empty| split_slice_by(&data, at_zero, policy, allow_empty);
+        assert_eq!(split(SeparatorPolicy::Drop, false), vec![&[1, 2][..], &[3]]);
+        let empty: &[i32] = &[];
+        assert_eq!(
+            split(SeparatorPolicy::Drop, true),
+            vec![empty, &[1, 2], empty, &[3], empty]
+        );
+        assert_eq!(
+            split(SeparatorPolicy::AttachToPrevious, false),
+            vec![&[0][..], &[1, 2, 0], &[0], &[3, 0]]
+        );
+        assert_eq!(
+            split(SeparatorPolicy::AttachToNext, false),
+            vec![&[0, 1, 2][..], &[0], &[0, 3], &[0]]
+        );
+        assert_eq!(
+            split(SeparatorPolicy::Isolate, false),
+            vec![&[0][..], &[1, 2], &[0], &[0], &[3], &[0]]
+        );
+        for policy in [
+            SeparatorPolicy::AttachToPrevious,
+            SeparatorPolicy::AttachToNext,
+            SeparatorPolicy::Isolate,
+        ] {
+            for allow_empty in [false, true] {
+                assert_eq!(split(policy, allow_empty).concat(), data);
+            }
+        }
+        assert!(split_slice_by(&data[..0], at_zero, SeparatorPolicy::Drop, true).is_empty());
+        assert_eq!(
+            split_slice_by(&data[1..3], at_zero, SeparatorPolicy::Isolate, true),
+            vec![&[1, 2][..]]
+        );
+    }
+
+    #[test]
+    fn window_pieces_never_crosses_a_boundary() {
+        let events = ["a", "b", "|", "c", "d", "e", "|", "f"];
+        let pieces = split_slice_by(&events, |e| *e == "|", SeparatorPolicy::Drop, false);
+        let windows = window_pieces(&pieces, 2, 0, true);
+        assert_eq!(
+            windows,
+            vec![
+                vec![&["a", "b"][..]],
+                vec![&["c", "d"][..], &["e"]],
+                vec![&["f"][..]],
+            ]
+        );
+        assert_eq!(window_pieces(&pieces, 2, 0, false)[2].len(), 0);
+    }
+
+    #[test]
+    fn grouped_by_never_crosses_a_key() {
+        let records = [
+            (1, 'a'),
+            (1, 'b'),
+            (1, 'c'),
+            (2, 'd'),
+            (3, 'e'),
+            (3, 'f'),
+            (3, 'g'),
+            (1, 'h'),
+        ];
+        let groups = SliceSplitter::new(&records, 3, 0, true).grouped_by(|r| r.0);
+        let keys: Vec<i32> = groups.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![1, 2, 3, 1]);
+        // Runs of exactly `n` make one window each; the single-element runs are kept tails.
+        assert_eq!(groups[0].1, vec![&records[0..3]]);
+        assert_eq!(groups[1].1, vec![&records[3..4]]);
+        assert_eq!(groups[2].1, vec![&records[4..7]]);
+        assert_eq!(groups[3].1, vec![&records[7..8]]);
+
+        let dropped = SliceSplitter::new(&records, 2, 0, false).grouped_by(|r| r.0);
+        let counts: Vec<usize> = dropped.iter().map(|(_, w)| w.len()).collect();
+        assert_eq!(counts, vec![1, 0, 1, 0]);
+        assert!(dropped.iter().flat_map(|(_, w)| w).all(|w| w.len() == 2));
+
+        let empty: [(i32, char); 0] = [];
+        assert!(SliceSplitter::new(&empty, 2, 0, true).grouped_by(|r| r.0).is_empty());
+    }
+}
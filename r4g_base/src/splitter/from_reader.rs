@@ -0,0 +1,635 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+
+use crate::splitter::utils::Tail;
+
+/// The windows of `Utf8Splitter`, computed over text that arrives in pieces.
+///
+/// Only the characters still needed are kept: those from the next window start on, or the last
+/// `n` for `Tail::PadBackward`. Unlike `sliding_windows`, an `n` of `0` produces no windows.
+pub(crate) struct CharWindowBuffer {
+    n: usize,
+    hop: usize,
+    tail: Tail,
+    buf: VecDeque<char>,
+    /// Character index (in the whole text) of `buf[0]`.
+    base: usize,
+    /// Number of characters seen so far.
+    total: usize,
+    /// Character index at which the next window starts.
+    next_start: usize,
+}
+
+impl CharWindowBuffer {
+    pub(crate) fn new(n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            n,
+            hop: if step == 0 { n } else { step },
+            tail,
+            buf: VecDeque::new(),
+            base: 0,
+            total: 0,
+            next_start: 0,
+        }
+    }
+
+    /// Append `text`, calling `emit` with every window it completes.
+    pub(crate) fn push(&mut self, text: &str, mut emit: impl FnMut(String)) {
+        if self.n == 0 {
+            return;
+        }
+        for c in text.chars() {
+            self.push_char(c, &mut emit);
+        }
+    }
+
+    /// Append one character, calling `emit` with every window it completes.
+    pub(crate) fn push_char(&mut self, c: char, mut emit: impl FnMut(String)) {
+        if self.n == 0 {
+            return;
+        }
+        self.buf.push_back(c);
+        self.total += 1;
+        while self.next_start + self.n <= self.total {
+            emit(self.collect_from(self.next_start, self.next_start + self.n));
+            self.next_start = self.next_start.saturating_add(self.hop);
+        }
+        let keep_from = match self.tail {
+            Tail::PadBackward => self.next_start.min(self.total.saturating_sub(self.n)),
+            _ => self.next_start.min(self.total),
+        };
+        while self.base < keep_from {
+            self.buf.pop_front();
+            self.base += 1;
+        }
+    }
+
+    /// The tail window after the last piece, if the tail policy keeps one.
+    pub(crate) fn finish(&self) -> Option<String> {
+        if self.n == 0 || self.next_start >= self.total {
+            return None;
+        }
+        match self.tail {
+            Tail::Keep => Some(self.collect_from(self.next_start, self.total)),
+            // Skip the padded window when the last full window already ends at the input's end.
+            Tail::PadBackward
+                if self.next_start >= self.hop
+                    && self.next_start - self.hop + self.n >= self.total =>
+            {
+                None
+            }
+            Tail::PadBackward => {
+                Some(self.collect_from(self.total.saturating_sub(self.n), self.total))
+            }
+            Tail::Drop | Tail::Exact => None,
+        }
+    }
+
+    fn collect_from(&self, start: usize, end: usize) -> String {
+        self.buf.range(start - self.base..end - self.base).collect()
+    }
+}
+
+/// A push-based splitter for UTF-8 bytes that arrive in pieces, e.g. from an async socket or
+/// file read loop, with the step and tail semantics of `Utf8Splitter`.
+///
+/// - `n`: The number of characters per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// A UTF-8 sequence split across two pushes is carried over to the next one. Nothing here blocks
+/// or depends on a runtime: an async caller pushes whatever each `read(..).await` returned and
+/// forwards the chunks, so chunks are produced exactly as fast as the input is consumed. With the
+/// `tokio` feature, `AsyncReaderSplitter` does this for any tokio `AsyncRead`.
+pub struct ByteStreamSplitter {
+    windows: CharWindowBuffer,
+    /// Bytes of an incomplete UTF-8 sequence at the end of the last push.
+    partial: Vec<u8>,
+}
+
+impl ByteStreamSplitter {
+    pub fn new(n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            windows: CharWindowBuffer::new(n, step, tail),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Append `bytes` and return the chunks they complete, in order.
+    ///
+    /// Invalid UTF-8 is an `InvalidData` error; the chunks completed by the bytes before it are
+    /// not returned, and the splitter should not be used further.
+    pub fn push(&mut self, bytes: &[u8]) -> io::Result<Vec<String>> {
+        let mut out = Vec::new();
+        self.feed(bytes, |chunk| out.push(chunk))?;
+        Ok(out)
+    }
+
+    /// End the stream, returning the tail chunk if the tail policy keeps one, or an
+    /// `UnexpectedEof` error if the input ended inside a UTF-8 sequence.
+    pub fn finish(self) -> io::Result<Option<String>> {
+        if !self.partial.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "input ends inside a UTF-8 sequence",
+            ));
+        }
+        Ok(self.windows.finish())
+    }
+
+    /// Decode `partial` followed by `bytes`, keeping an incomplete trailing sequence in
+    /// `partial`. Chunks completed by the valid prefix are emitted even when invalid data
+    /// follows.
+    fn feed(&mut self, bytes: &[u8], emit: impl FnMut(String)) -> io::Result<()> {
+        self.partial.extend_from_slice(bytes);
+        let (valid, invalid) = match std::str::from_utf8(&self.partial) {
+            Ok(s) => (s.len(), None),
+            Err(e) if e.error_len().is_none() => (e.valid_up_to(), None),
+            Err(e) => (e.valid_up_to(), Some(e)),
+        };
+        let text = std::str::from_utf8(&self.partial[..valid]).expect("validated above");
+        self.windows.push(text, emit);
+        self.partial.drain(..valid);
+        match invalid {
+            Some(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The decoding half of the reader splitters: chunks completed by the reads so far, and an
+/// error to yield once they are drained.
+struct ReadQueue {
+    inner: Option<ByteStreamSplitter>,
+    ready: VecDeque<String>,
+    /// An error to yield once the chunks before it are drained.
+    error: Option<io::Error>,
+}
+
+impl ReadQueue {
+    fn new(n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            inner: Some(ByteStreamSplitter::with_tail(n, step, tail)),
+            ready: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    /// The next chunk or error to yield, if one is ready.
+    fn pop(&mut self) -> Option<io::Result<String>> {
+        match self.ready.pop_front() {
+            Some(chunk) => Some(Ok(chunk)),
+            None => self.error.take().map(Err),
+        }
+    }
+
+    /// Whether the input has ended, so no further reads are needed.
+    fn is_done(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// Handle the bytes of one read; an empty read is the end of the input.
+    fn on_read(&mut self, read: io::Result<&[u8]>) {
+        let result = match read {
+            Ok([]) => {
+                let tail = self
+                    .inner
+                    .take()
+                    .map_or(Ok(None), ByteStreamSplitter::finish);
+                tail.map(|tail| self.ready.extend(tail))
+            }
+            Ok(bytes) => match self.inner.as_mut() {
+                Some(inner) => {
+                    let ready = &mut self.ready;
+                    inner.feed(bytes, |chunk| ready.push_back(chunk))
+                }
+                None => Ok(()),
+            },
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            self.inner = None;
+            self.error = Some(e);
+        }
+    }
+}
+
+/// Size of the buffer each read fills.
+const READ_SIZE: usize = 8 * 1024;
+
+/// A splitter that reads text from any `io::Read` in buffered pieces and yields owned chunks of
+/// `n` characters, with the step and tail semantics of `Utf8Splitter`.
+///
+/// - `reader`: The UTF-8 source.
+/// - `n`: The number of characters per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// Reads are decoded by a `ByteStreamSplitter`, so a UTF-8 sequence split across two reads is
+/// carried over to the next one. Read errors, invalid UTF-8 and input ending inside a sequence
+/// are yielded as `Err` items, after which iteration stops. Memory use is bounded by the read
+/// buffer plus one window, not by the input size.
+pub struct ReaderSplitter<R> {
+    reader: R,
+    read_buf: Box<[u8]>,
+    queue: ReadQueue,
+}
+
+impl<R: Read> ReaderSplitter<R> {
+    pub fn new(reader: R, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(reader, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(reader: R, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            reader,
+            read_buf: vec![0; READ_SIZE].into_boxed_slice(),
+            queue: ReadQueue::new(n, step, tail),
+        }
+    }
+}
+
+impl<R: Read> Iterator for ReaderSplitter<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.queue.pop() {
+                return Some(item);
+            }
+            if self.queue.is_done() {
+                return None;
+            }
+            let read = self.reader.read(&mut self.read_buf);
+            self.queue.on_read(read.map(|len| &self.read_buf[..len]));
+        }
+    }
+}
+
+/// The async counterpart of `ReaderSplitter`: a `Stream` of owned chunks of `n` characters read
+/// from a tokio `AsyncRead`, with the step and tail semantics of `Utf8Splitter`.
+///
+/// - `reader`: The UTF-8 source.
+/// - `n`: The number of characters per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// Reads are decoded by a `ByteStreamSplitter`, exactly as in `ReaderSplitter`, and errors are
+/// yielded the same way. The reader is only polled when the stream is polled and no chunk is
+/// ready, so a slow consumer holds back the reader instead of buffering the input.
+#[cfg(feature = "tokio")]
+pub struct AsyncReaderSplitter<R> {
+    reader: R,
+    read_buf: Box<[u8]>,
+    queue: ReadQueue,
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> AsyncReaderSplitter<R> {
+    pub fn new(reader: R, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(reader, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(reader: R, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            reader,
+            read_buf: vec![0; READ_SIZE].into_boxed_slice(),
+            queue: ReadQueue::new(n, step, tail),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> futures_core::Stream for AsyncReaderSplitter<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.queue.pop() {
+                return Poll::Ready(Some(item));
+            }
+            if this.queue.is_done() {
+                return Poll::Ready(None);
+            }
+            let mut buf = tokio::io::ReadBuf::new(&mut this.read_buf);
+            let read = match std::pin::Pin::new(&mut this.reader).poll_read(cx, &mut buf) {
+                Poll::Ready(read) => read.map(|()| buf.filled().len()),
+                Poll::Pending => return Poll::Pending,
+            };
+            this.queue.on_read(read.map(|len| &this.read_buf[..len]));
+        }
+    }
+}
+
+/// Split the text read from `reader` into chunks of `n` characters as a `Stream`; see
+/// `AsyncReaderSplitter`.
+#[cfg(feature = "tokio")]
+pub fn split_reader<R: tokio::io::AsyncRead + Unpin>(
+    reader: R,
+    n: usize,
+    step: usize,
+    keep_tail: bool,
+) -> AsyncReaderSplitter<R> {
+    AsyncReaderSplitter::new(reader, n, step, keep_tail)
+}
+
+/// A push-based splitter for text that arrives in pieces, e.g. a streamed model response. Chunks
+/// of `n` characters are returned as soon as they are complete.
+///
+/// - `n`: The number of characters per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// The chunks are those of `Utf8Splitter` over the concatenation of all pushes, however the text
+/// is divided: overlapping characters are carried over between pushes. Pieces are `&str`, so a
+/// push never ends inside a character; it may end inside a grapheme cluster, which is counted as
+/// its separate characters like everywhere else.
+pub struct StreamingUtf8Splitter {
+    windows: CharWindowBuffer,
+}
+
+impl StreamingUtf8Splitter {
+    pub fn new(n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            windows: CharWindowBuffer::new(n, step, tail),
+        }
+    }
+
+    /// Append `text` and return the chunks it completes, in order.
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        self.windows.push(text, |chunk| out.push(chunk));
+        out
+    }
+
+    /// End the stream, returning the tail chunk if the tail policy keeps one.
+    pub fn finish(self) -> Option<String> {
+        self.windows.finish()
+    }
+}
+
+/// A splitter over an iterator of characters, e.g. the output of a decoder or a filter chain,
+/// yielding owned chunks of `n` characters with the step and tail semantics of `Utf8Splitter`.
+///
+/// - `chars`: The characters to split.
+/// - `n`: The number of characters per chunk.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// Characters are pulled only as chunks are requested. With `step < n` the overlapping
+/// characters are kept to be emitted again; with `step > n` the characters between windows are
+/// consumed and dropped. At most one window of characters is held at a time. Unlike
+/// `Utf8Splitter`, an `n` of `0` yields no chunks rather than empty ones.
+pub struct CharIterSplitter<I> {
+    chars: I,
+    windows: CharWindowBuffer,
+    ready: VecDeque<String>,
+    finished: bool,
+}
+
+impl<I: Iterator<Item = char>> CharIterSplitter<I> {
+    pub fn new(chars: I, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(chars, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(chars: I, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            chars,
+            windows: CharWindowBuffer::new(n, step, tail),
+            ready: VecDeque::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for CharIterSplitter<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(chunk) = self.ready.pop_front() {
+                return Some(chunk);
+            }
+            if self.finished {
+                return None;
+            }
+            match self.chars.next() {
+                Some(c) => {
+                    let ready = &mut self.ready;
+                    self.windows.push_char(c, |chunk| ready.push_back(chunk));
+                }
+                None => {
+                    self.finished = true;
+                    return self.windows.finish();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::splitter::from_char::Utf8Splitter;
+
+    /// A reader that hands out at most `size` bytes per call.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        size: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let k = self.size.min(buf.len()).min(self.data.len());
+            buf[..k].copy_from_slice(&self.data[..k]);
+            self.data = &self.data[k..];
+            Ok(k)
+        }
+    }
+
+    #[test]
+    fn matches_utf8_splitter_across_seams() {
+        let s = "añ😀bc\u{301}dé€fg 😀😀 hij";
+        for tail in [Tail::Keep, Tail::Drop, Tail::PadBackward] {
+            for (n, step) in [(3, 0), (3, 1), (4, 6), (5, 2), (40, 0)] {
+                let expected = Utf8Splitter::with_tail(s, n, step, tail).out();
+                for size in [1, 3, 7, 1024] {
+                    let reader = Trickle {
+                        data: s.as_bytes(),
+                        size,
+                    };
+                    let got: Vec<String> = ReaderSplitter::with_tail(reader, n, step, tail)
+                        .collect::<io::Result<_>>()
+                        .unwrap();
+                    assert_eq!(got, expected, "n={n} step={step} {tail:?} size={size}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_sequence_is_an_error() {
+        let data = "ab😀".as_bytes();
+        let reader = Trickle {
+            data: &data[..data.len() - 1],
+            size: 3,
+        };
+        let items: Vec<_> = ReaderSplitter::new(reader, 1, 0, true).collect();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_deref().unwrap(), "a");
+        assert_eq!(items[1].as_deref().unwrap(), "b");
+        assert_eq!(
+            items[2].as_ref().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error() {
+        let data = b"abc\xFFdef";
+        let mut it = ReaderSplitter::new(&data[..], 2, 0, true);
+        assert_eq!(it.next().unwrap().unwrap(), "ab");
+        let err = it.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(it.next().is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_stream_matches_sync_splitter() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+        use tokio::io::AsyncWriteExt;
+
+        let s = "añ😀bc\u{301}dé€fg 😀😀 hij ".repeat(20);
+        for (n, step, keep_tail) in [(3, 0, true), (3, 1, false), (4, 6, true), (7, 2, true)] {
+            let expected: Vec<String> = ReaderSplitter::new(s.as_bytes(), n, step, keep_tail)
+                .collect::<io::Result<_>>()
+                .unwrap();
+
+            // A pipe smaller than the input, written a few bytes at a time, so seams fall inside
+            // characters and the reader has to wait on the writer.
+            let (mut tx, rx) = tokio::io::duplex(16);
+            let bytes = s.as_bytes().to_vec();
+            let writer = async move {
+                for piece in bytes.chunks(3) {
+                    tx.write_all(piece).await.unwrap();
+                }
+            };
+            let reader = async {
+                let mut stream = split_reader(rx, n, step, keep_tail);
+                let mut got = Vec::new();
+                while let Some(item) =
+                    std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+                {
+                    got.push(item.unwrap());
+                }
+                got
+            };
+            let ((), got) = tokio::join!(writer, reader);
+            assert_eq!(got, expected, "n={n} step={step} keep_tail={keep_tail}");
+        }
+    }
+
+    #[test]
+    fn streaming_pushes_match_one_push() {
+        let s = "añ😀bc\u{301}dé€fg 😀😀 hij";
+        for tail in [Tail::Keep, Tail::Drop, Tail::PadBackward] {
+            for (n, step) in [(3, 0), (3, 1), (4, 6), (5, 2), (40, 0)] {
+                let mut by_char = StreamingUtf8Splitter::with_tail(n, step, tail);
+                let mut chunks = Vec::new();
+                let mut buf = [0; 4];
+                for c in s.chars() {
+                    chunks.extend(by_char.push(c.encode_utf8(&mut buf)));
+                }
+                chunks.extend(by_char.finish());
+
+                let mut whole = StreamingUtf8Splitter::with_tail(n, step, tail);
+                let mut expected = whole.push(s);
+                expected.extend(whole.finish());
+                assert_eq!(chunks, expected, "n={n} step={step} {tail:?}");
+                assert_eq!(expected, Utf8Splitter::with_tail(s, n, step, tail).out());
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_emits_as_soon_as_complete() {
+        let mut sp = StreamingUtf8Splitter::new(4, 2, true);
+        assert!(sp.push("abc").is_empty());
+        assert_eq!(sp.push("de"), vec!["abcd"]);
+        assert_eq!(sp.push("fgh"), vec!["cdef", "efgh"]);
+        assert_eq!(sp.push(""), Vec::<String>::new());
+        assert_eq!(sp.finish().as_deref(), Some("gh"));
+    }
+
+    #[test]
+    fn byte_stream_matches_utf8_splitter() {
+        let s = "añ😀bc\u{301}dé€fg 😀😀 hij";
+        for (n, step, keep_tail) in [(3, 0, true), (3, 1, false), (4, 6, true), (5, 2, true)] {
+            let mut sp = ByteStreamSplitter::new(n, step, keep_tail);
+            let mut chunks = Vec::new();
+            for piece in s.as_bytes().chunks(3) {
+                chunks.extend(sp.push(piece).unwrap());
+            }
+            chunks.extend(sp.finish().unwrap());
+            assert_eq!(chunks, Utf8Splitter::new(s, n, step, keep_tail).out());
+        }
+
+        let mut cut = ByteStreamSplitter::new(2, 0, true);
+        assert_eq!(cut.push(&"ab😀".as_bytes()[..4]).unwrap(), vec!["ab"]);
+        assert_eq!(
+            cut.finish().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        let mut bad = ByteStreamSplitter::new(2, 0, true);
+        let err = bad.push(b"a\xFFb").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn char_iter_matches_utf8_splitter_on_grid() {
+        let s = "añ😀bc\u{301}dé€fg 😀😀 hij";
+        for n in 0..8 {
+            for step in 0..10 {
+                if n == 0 && step == 0 {
+                    continue;
+                }
+                for keep_tail in [true, false] {
+                    let got: Vec<String> =
+                        CharIterSplitter::new(s.chars(), n, step, keep_tail).collect();
+                    let expected = if n == 0 {
+                        Vec::new()
+                    } else {
+                        Utf8Splitter::new(s, n, step, keep_tail).out()
+                    };
+                    assert_eq!(got, expected, "n={n} step={step} keep_tail={keep_tail}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn char_iter_pulls_lazily() {
+        let mut pulled = 0;
+        let chars = "abcdefghij".chars().inspect(|_| pulled += 1);
+        let first: Vec<String> = CharIterSplitter::new(chars, 3, 2, true).take(2).collect();
+        assert_eq!(first, vec!["abc", "cde"]);
+        assert_eq!(pulled, 5);
+    }
+}
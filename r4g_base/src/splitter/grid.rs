@@ -0,0 +1,197 @@
+use std::ops::Range;
+
+use crate::splitter::utils::{sliding_windows, window_count, SplitError, Tail};
+
+/// A 2D block of a `GridSplitter`: its column and row ranges, and one borrowed slice per row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridBlock<'a, T> {
+    /// Columns of the block.
+    pub x: Range<usize>,
+    /// Rows of the block.
+    pub y: Range<usize>,
+    /// The block's rows, top to bottom, each `x.len()` elements long.
+    pub rows: Vec<&'a [T]>,
+}
+
+impl<T: Clone> GridBlock<'_, T> {
+    /// Copy the block into one row-major vector of `x.len() * y.len()` elements.
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut out = Vec::with_capacity(self.x.len() * self.y.len());
+        for row in &self.rows {
+            out.extend_from_slice(row);
+        }
+        out
+    }
+}
+
+/// A splitter for dividing a row-major grid (an image, a matrix) into 2D blocks.
+///
+/// - `data`: The grid, row after row.
+/// - `width`: Number of columns; `data.len()` must be a multiple of it.
+/// - `block_w`, `block_h`: Block size in columns and rows.
+/// - `step_x`, `step_y`: Step between block starts on each axis (`0` means a step of the block
+///   size, i.e., non-overlapping).
+/// - `tail`: How to handle remaining columns and rows at the end (see `Tail`), applied to each
+///   axis independently.
+///
+/// Blocks are laid out with `sliding_windows` on each axis and yielded row of blocks by row of
+/// blocks, left to right.
+pub struct GridSplitter<'a, T> {
+    data: &'a [T],
+    width: usize,
+    block_w: usize,
+    block_h: usize,
+    step_x: usize,
+    step_y: usize,
+    tail: Tail,
+}
+
+impl<'a, T> GridSplitter<'a, T> {
+    /// Create a new GridSplitter.
+    ///
+    /// Returns `SplitError::RaggedGrid` if `data.len()` is not a multiple of `width` (a `width`
+    /// of `0` is only accepted for empty `data`).
+    pub fn new(
+        data: &'a [T],
+        width: usize,
+        block_w: usize,
+        block_h: usize,
+        step_x: usize,
+        step_y: usize,
+        keep_tail: bool,
+    ) -> Result<Self, SplitError> {
+        let len = data.len();
+        if len.checked_rem(width).unwrap_or(len) != 0 {
+            return Err(SplitError::RaggedGrid { len, width });
+        }
+        Ok(Self {
+            data,
+            width,
+            block_w,
+            block_h,
+            step_x,
+            step_y,
+            tail: keep_tail.into(),
+        })
+    }
+
+    /// Handle the remaining columns and rows with `tail`.
+    pub fn tail(mut self, tail: Tail) -> Self {
+        self.tail = tail;
+        self
+    }
+
+    /// Number of rows in the grid.
+    pub fn height(&self) -> usize {
+        self.data.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    /// Return the column and row ranges of each block, without borrowing any data.
+    pub fn block_ranges(&self) -> Vec<(Range<usize>, Range<usize>)> {
+        let xs: Vec<Range<usize>> =
+            sliding_windows(self.width, self.block_w, self.step_x, self.tail).collect();
+        let mut out = Vec::with_capacity(self.count());
+        for y in sliding_windows(self.height(), self.block_h, self.step_y, self.tail) {
+            out.extend(xs.iter().map(|x| (x.clone(), y.clone())));
+        }
+        out
+    }
+
+    /// Return the blocks, each as borrowed row slices.
+    pub fn split(&self) -> Vec<GridBlock<'a, T>> {
+        let (data, width) = (self.data, self.width);
+        self.block_ranges()
+            .into_iter()
+            .map(|(x, y)| GridBlock {
+                rows: y
+                    .clone()
+                    .map(|row| &data[row * width + x.start..row * width + x.end])
+                    .collect(),
+                x,
+                y,
+            })
+            .collect()
+    }
+
+    /// Return the number of blocks without building them.
+    pub fn count(&self) -> usize {
+        window_count(self.width, self.block_w, self.step_x, self.tail)
+            * window_count(self.height(), self.block_h, self.step_y, self.tail)
+    }
+
+    /// Return each block copied into a row-major vector (see `GridBlock::to_vec`).
+    pub fn out(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.split().iter().map(GridBlock::to_vec).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5 x 4 grid holding `10 * row + column`.
+    fn grid() -> Vec<u32> {
+        (0..4)
+            .flat_map(|y| (0..5).map(move |x| 10 * y + x))
+            .collect()
+    }
+
+    #[test]
+    fn blocks_with_tails_on_both_axes() {
+        let data = grid();
+        let sp = GridSplitter::new(&data, 5, 2, 3, 0, 0, true).unwrap();
+        assert_eq!(sp.height(), 4);
+        let ranges = sp.block_ranges();
+        assert_eq!(ranges.len(), sp.count());
+        assert_eq!(
+            ranges,
+            vec![
+                (0..2, 0..3),
+                (2..4, 0..3),
+                (4..5, 0..3),
+                (0..2, 3..4),
+                (2..4, 3..4),
+                (4..5, 3..4),
+            ]
+        );
+        let blocks = sp.split();
+        assert_eq!(blocks[1].rows, vec![&[2, 3][..], &[12, 13], &[22, 23]]);
+        assert_eq!(blocks[5].rows, vec![&[34][..]]);
+        assert_eq!(sp.out()[0], vec![0, 1, 10, 11, 20, 21]);
+
+        let dropped = GridSplitter::new(&data, 5, 2, 3, 0, 0, false).unwrap();
+        assert_eq!(dropped.block_ranges(), vec![(0..2, 0..3), (2..4, 0..3)]);
+    }
+
+    #[test]
+    fn overlapping_blocks_and_padded_tails() {
+        let data = grid();
+        let sp = GridSplitter::new(&data, 5, 3, 2, 2, 1, false).unwrap();
+        assert_eq!(sp.count(), 2 * 3);
+        assert_eq!(sp.split()[3].rows, vec![&[12, 13, 14][..], &[22, 23, 24]]);
+
+        let padded = GridSplitter::new(&data, 5, 2, 3, 0, 0, true)
+            .unwrap()
+            .tail(Tail::PadBackward);
+        let ranges = padded.block_ranges();
+        assert_eq!(ranges[2], (3..5, 0..3));
+        assert_eq!(ranges[5], (3..5, 1..4));
+        assert!(padded.out().iter().all(|block| block.len() == 6));
+    }
+
+    #[test]
+    fn rejects_ragged_grids() {
+        let data = grid();
+        assert_eq!(
+            GridSplitter::new(&data, 3, 2, 2, 0, 0, true).err(),
+            Some(SplitError::RaggedGrid { len: 20, width: 3 })
+        );
+        assert!(GridSplitter::new(&data, 0, 2, 2, 0, 0, true).is_err());
+        let empty = GridSplitter::new(&data[..0], 0, 2, 2, 0, 0, true).unwrap();
+        assert_eq!(empty.count(), 0);
+        assert!(empty.split().is_empty());
+    }
+}
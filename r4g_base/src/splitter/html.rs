@@ -0,0 +1,216 @@
+use scraper::{Html, Node};
+
+use crate::splitter::from_char::utf8_by_chars_borrowed;
+use crate::splitter::utils::{to_owned_all, window_count, Tail};
+
+/// Elements whose start or end marks a paragraph break in the extracted text.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content is never visible text.
+const SKIP_TAGS: &[&str] = &["head", "script", "style", "noscript", "template"];
+
+fn push_char(para: &mut String, c: char) {
+    if c.is_whitespace() {
+        if !para.is_empty() && !para.ends_with(' ') {
+            para.push(' ');
+        }
+    } else {
+        para.push(c);
+    }
+}
+
+fn flush(paras: &mut Vec<String>, para: &mut String) {
+    let trimmed = para.trim_end();
+    if !trimmed.is_empty() {
+        paras.push(trimmed.to_string());
+    }
+    para.clear();
+}
+
+/// Extracts the visible text of an HTML document.
+///
+/// The document is parsed with `scraper` (html5ever), so malformed markup, comments, CDATA,
+/// raw-text elements and character references are handled as a browser would. The text of the
+/// resulting DOM is collected with `head`, `script`, `style`, `noscript` and `template` content
+/// dropped, whitespace collapsed, and block-level element boundaries turned into paragraph
+/// breaks (`"\n\n"`).
+pub fn html_to_text(html: &str) -> String {
+    let doc = Html::parse_document(html);
+    let mut paras = Vec::new();
+    let mut para = String::new();
+
+    // Depth-first over the DOM with an explicit stack, so deep nesting cannot overflow; the
+    // `true` entries mark the end of an element.
+    let mut stack = vec![(doc.tree.root(), false)];
+    while let Some((node, leaving)) = stack.pop() {
+        match node.value() {
+            Node::Element(el) if leaving => {
+                if BLOCK_TAGS.contains(&el.name()) {
+                    flush(&mut paras, &mut para);
+                }
+                continue;
+            }
+            Node::Element(el) if SKIP_TAGS.contains(&el.name()) => continue,
+            Node::Element(el) => {
+                if BLOCK_TAGS.contains(&el.name()) {
+                    flush(&mut paras, &mut para);
+                }
+                stack.push((node, true));
+            }
+            Node::Text(text) => text.chars().for_each(|c| push_char(&mut para, c)),
+            Node::Document | Node::Fragment => {}
+            _ => continue,
+        }
+        stack.extend(node.children().rev().map(|child| (child, false)));
+    }
+    flush(&mut paras, &mut para);
+    paras.join("\n\n")
+}
+
+/// A splitter that extracts visible text from HTML and windows it by character count.
+///
+/// - `html`: The HTML source.
+/// - `n`: The number of characters per substring.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// The extracted text is owned by the splitter; `split` borrows from it.
+pub struct HtmlSplitter {
+    text: String,
+    n: usize,
+    step: usize,
+    tail: Tail,
+}
+
+impl HtmlSplitter {
+    pub fn new(html: &str, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(html, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(html: &str, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            text: html_to_text(html),
+            n,
+            step,
+            tail,
+        }
+    }
+
+    /// The extracted text, with paragraphs separated by `"\n\n"`.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn split(&self) -> Vec<&str> {
+        utf8_by_chars_borrowed(&self.text, self.n, self.step, self.tail)
+    }
+
+    /// Return the number of chunks, computed from the character count of the extracted text.
+    pub fn count(&self) -> usize {
+        window_count(self.text.chars().count(), self.n, self.step, self.tail)
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>T</title><style>p { color: red; }</style></head>
+<body><h1>Hello</h1><p>First <b>bold</b> para.</p>
+<script>var x = "<p>nope</p>";</script><!-- comment -->
+<div data-x="a > b">Second&nbsp;&amp; last</div></body></html>"#;
+
+    #[test]
+    fn extracts_visible_text_with_paragraph_breaks() {
+        assert_eq!(
+            html_to_text(PAGE),
+            "Hello\n\nFirst bold para.\n\nSecond & last"
+        );
+    }
+
+    #[test]
+    fn lone_less_than_is_text() {
+        assert_eq!(html_to_text("<p>a < b and c <= d</p>"), "a < b and c <= d");
+        assert_eq!(html_to_text("1 <2> 3 <"), "1 <2> 3 <");
+        // `</` not followed by a letter opens a bogus comment, as in a browser.
+        assert_eq!(html_to_text("x </ y <b>z</b>"), "x z");
+    }
+
+    #[test]
+    fn skipped_elements_end_at_their_own_end_tag() {
+        let html = "<SCRIPT>if (a </b) {}</Script >after<STYLE>p{}</styles></StYlE>done";
+        assert_eq!(html_to_text(html), "afterdone");
+        // Text after the skipped element keeps its case.
+        assert_eq!(html_to_text("<style>x</style>MiXeD"), "MiXeD");
+    }
+
+    #[test]
+    fn parses_entities_comments_and_malformed_markup() {
+        assert_eq!(
+            html_to_text("<p>caf&eacute;&hellip; &#x1F600; &amp;lt;</p>"),
+            "café… 😀 &lt;"
+        );
+        assert_eq!(html_to_text("a<!-- <p> > -->b"), "ab");
+        assert_eq!(
+            html_to_text("<svg><![CDATA[x < y]]></svg><p>one<p>two</div>three"),
+            "x < y\n\none\n\ntwothree"
+        );
+        assert_eq!(html_to_text("<ul><li>a<li>b</ul>"), "a\n\nb");
+    }
+
+    #[test]
+    fn chunks_contain_only_readable_text() {
+        let chunks = HtmlSplitter::new(PAGE, 10, 0, true).out();
+        assert!(chunks.iter().all(|c| !c.contains('<') && !c.contains('>')));
+        assert!(chunks
+            .iter()
+            .all(|c| !c.contains("color") && !c.contains("nope")));
+        assert_eq!(chunks.concat(), html_to_text(PAGE));
+    }
+
+    #[test]
+    fn count_matches_split() {
+        for n in 1..12 {
+            let sp = HtmlSplitter::new(PAGE, n, 3, true);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use crate::splitter::utils::{back_chars, forward_chars, to_owned_all, Tail};
+
+/// Longest entity body (between `&` and `;`) treated as an entity.
+const MAX_ENTITY_LEN: usize = 32;
+
+/// Returns the byte ranges of `<...>` tags and `&...;` entities in `input`.
+///
+/// A `<` opens a tag only when followed by an ASCII letter, `/`, `!` or `?`; quoted attribute
+/// values may contain `>`. An unterminated tag runs to the end of the input. An entity is `&`
+/// followed by at most `MAX_ENTITY_LEN` ASCII alphanumerics or `#`, then `;`.
+pub fn html_spans(input: &str) -> Vec<Range<usize>> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0usize;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' if bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || matches!(b, b'/' | b'!' | b'?')) =>
+            {
+                let start = i;
+                let mut quote = None;
+                i += 1;
+                while i < bytes.len() {
+                    match (quote, bytes[i]) {
+                        (Some(q), b) if b == q => quote = None,
+                        (None, b'"' | b'\'') => quote = Some(bytes[i]),
+                        (None, b'>') => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                out.push(start..i);
+            }
+            b'&' => {
+                let body = bytes[i + 1..]
+                    .iter()
+                    .take(MAX_ENTITY_LEN + 1)
+                    .take_while(|b| b.is_ascii_alphanumeric() || **b == b'#')
+                    .count();
+                if body > 0 && body <= MAX_ENTITY_LEN && bytes.get(i + 1 + body) == Some(&b';') {
+                    out.push(i..i + body + 2);
+                    i += body + 2;
+                } else {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    out
+}
+
+/// Character windows over `input` whose boundaries never fall strictly inside a span.
+///
+/// A window end inside a span is pulled back to the span start, or pushed to the span end when
+/// the window itself starts at the span. The next window never starts past the previous end
+/// unless `step > n`, so no text is dropped by the adjustment.
+pub fn html_aware_windows(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+) -> Vec<Range<usize>> {
+    let tail = tail.into();
+    let len = input.len();
+    let hop = if step == 0 { n } else { step };
+    let spans = html_spans(input);
+    let inside = |p: usize| spans.iter().find(|s| s.start < p && p < s.end);
+    let mut out = Vec::new();
+    if n == 0 {
+        return out;
+    }
+
+    let mut start = 0usize;
+    while start < len {
+        let raw_end = forward_chars(input, start, n, len);
+        if input[start..raw_end].chars().count() < n {
+            match tail {
+                Tail::Keep => out.push(start..len),
+                Tail::PadBackward if out.last().is_some_and(|r| r.end >= len) => {}
+                Tail::PadBackward => {
+                    let mut s = back_chars(input, len, n, 0);
+                    if let Some(span) = inside(s) {
+                        s = span.start;
+                    }
+                    out.push(s..len);
+                }
+                Tail::Drop | Tail::Exact => {}
+            }
+            break;
+        }
+        let end = match inside(raw_end) {
+            Some(span) if span.start > start => span.start,
+            Some(span) => span.end,
+            None => raw_end,
+        };
+        out.push(start..end);
+
+        let mut next = forward_chars(input, start, hop, len);
+        if let Some(span) = inside(next) {
+            next = if span.start > start {
+                span.start
+            } else {
+                span.end
+            };
+        }
+        if hop <= n {
+            next = next.min(end);
+        }
+        start = next;
+    }
+    out
+}
+
+/// A splitter for raw HTML that never cuts inside a tag or entity.
+///
+/// - `input`: The HTML source.
+/// - `n`: The target number of characters per substring.
+/// - `step`: Step between window starts (`0` means no overlap).
+/// - `tail`: How to handle any remaining characters at the end (see `Tail`).
+///
+/// Windows are `n` characters unless a boundary had to be moved out of a tag or entity.
+pub struct HtmlAwareSplitter<'a> {
+    input: &'a str,
+    n: usize,
+    step: usize,
+    tail: Tail,
+}
+
+impl<'a> HtmlAwareSplitter<'a> {
+    pub fn new(input: &'a str, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(input, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(input: &'a str, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            input,
+            n,
+            step,
+            tail,
+        }
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        html_aware_windows(self.input, self.n, self.step, self.tail)
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+
+    /// Return the number of chunks, computing the windows without slicing them.
+    pub fn count(&self) -> usize {
+        html_aware_windows(self.input, self.n, self.step, self.tail).len()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_tags_and_entities() {
+        let s = "a<b>x&amp;y</b> & c&nosemicolon";
+        let spans: Vec<_> = html_spans(s).into_iter().map(|r| &s[r]).collect();
+        assert_eq!(spans, vec!["<b>", "&amp;", "</b>"]);
+    }
+
+    #[test]
+    fn quoted_gt_inside_attribute() {
+        let s = r#"<div title="a > b" data-x='>'>hi</div>"#;
+        let spans: Vec<_> = html_spans(s).into_iter().map(|r| &s[r]).collect();
+        assert_eq!(spans, vec![r#"<div title="a > b" data-x='>'>"#, "</div>"]);
+    }
+
+    #[test]
+    fn never_cuts_inside_tag_or_entity() {
+        let s = r#"<p class="x > y">Fish &amp; chips</p><div>more text</div>"#;
+        let chunks = HtmlAwareSplitter::new(s, 7, 0, true).split();
+        assert_eq!(chunks.concat(), s);
+        for c in &chunks {
+            assert!(!c.ends_with("<di") && !c.ends_with("&am"));
+        }
+        let spans = html_spans(s);
+        let mut pos = 0;
+        for c in &chunks {
+            pos += c.len();
+            assert!(!spans.iter().any(|sp| sp.start < pos && pos < sp.end));
+        }
+    }
+
+    #[test]
+    fn overlapping_windows_keep_all_text() {
+        let s = "ab&amp;cd<br>efgh";
+        let ranges = html_aware_windows(s, 4, 2, true);
+        let mut covered = 0;
+        for r in &ranges {
+            assert!(r.start <= covered);
+            covered = covered.max(r.end);
+        }
+        assert_eq!(covered, s.len());
+    }
+
+    #[test]
+    fn count_matches_split() {
+        let s = "ab&amp;cd<br>efgh";
+        for n in 1..8 {
+            let sp = HtmlAwareSplitter::new(s, n, 0, true);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+
+    #[test]
+    fn pad_backward_does_not_repeat_the_last_window() {
+        assert_eq!(
+            html_aware_windows("abcdef", 4, 2, Tail::PadBackward),
+            vec![0..4, 2..6]
+        );
+        assert_eq!(
+            html_aware_windows("abcdefg", 4, 2, Tail::PadBackward),
+            vec![0..4, 2..6, 3..7]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+use std::ops::Range;
+
+use serde_json::value::RawValue;
+
+/// Why the input could not be read as a JSON array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonError {
+    /// The input does not start with `[` (after whitespace).
+    NotAnArray,
+    /// The array is malformed or truncated at this byte offset.
+    Malformed(usize),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::NotAnArray => write!(f, "input is not a JSON array"),
+            JsonError::Malformed(offset) => write!(f, "malformed JSON at byte {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Byte offset of the 1-based `line` and `column` of a `serde_json` error, clamped to `input`.
+fn error_offset(input: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = input
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(input.len())
+}
+
+/// Returns the byte range of each element of the top-level JSON array in `input`.
+///
+/// The input is parsed with `serde_json`, so every element is validated, scalars included; each
+/// range covers the element's source text exactly as written.
+pub fn json_array_elements(input: &str) -> Result<Vec<Range<usize>>, JsonError> {
+    let Some(open) = input.find(|c: char| !matches!(c, ' ' | '\t' | '\n' | '\r')) else {
+        return Err(JsonError::NotAnArray);
+    };
+    if !input[open..].starts_with('[') {
+        return Err(JsonError::NotAnArray);
+    }
+    let elements: Vec<&RawValue> = serde_json::from_str(input).map_err(|e| {
+        JsonError::Malformed(if e.is_eof() {
+            input.len()
+        } else {
+            error_offset(input, e.line(), e.column())
+        })
+    })?;
+
+    // Between two elements there is only whitespace and a comma, and an element never starts
+    // with either, so the first match after the previous element is the element itself.
+    let mut pos = open + 1;
+    let mut out = Vec::with_capacity(elements.len());
+    for raw in elements {
+        let text = raw.get();
+        let start = pos + input[pos..].find(text).unwrap_or(0);
+        out.push(start..start + text.len());
+        pos = start + text.len();
+    }
+    Ok(out)
+}
+
+/// A chunk of a JSON array: a smaller array holding consecutive elements of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonChunk {
+    /// The chunk as a JSON array, elements separated by `,` without extra whitespace.
+    pub json: String,
+    /// Indices of the input elements in this chunk.
+    pub elements: Range<usize>,
+    /// Set when the chunk holds a single element that alone exceeds `max_bytes`.
+    pub oversized: bool,
+}
+
+/// A splitter that packs the elements of a JSON array into smaller arrays of at most `max_bytes`.
+///
+/// - `input`: A JSON array.
+/// - `max_bytes`: Maximum length of each emitted array, brackets and commas included.
+///
+/// Elements are never split and are copied verbatim (so whitespace inside them is kept); only the
+/// whitespace between elements is dropped. An element too large for any chunk is emitted alone
+/// with `oversized` set.
+pub struct JsonArraySplitter<'a> {
+    input: &'a str,
+    max_bytes: usize,
+}
+
+impl<'a> JsonArraySplitter<'a> {
+    pub fn new(input: &'a str, max_bytes: usize) -> Self {
+        Self { input, max_bytes }
+    }
+
+    pub fn split(&self) -> Result<Vec<JsonChunk>, JsonError> {
+        let elements = json_array_elements(self.input)?;
+        let mut out = Vec::new();
+        let mut first = 0usize;
+        let mut size = 2usize;
+        for (k, r) in elements.iter().enumerate() {
+            let extra = r.len() + usize::from(k > first);
+            if k > first && size + extra > self.max_bytes {
+                out.push(self.chunk(&elements, first..k));
+                first = k;
+                size = 2 + r.len();
+            } else {
+                size += extra;
+            }
+        }
+        if first < elements.len() {
+            out.push(self.chunk(&elements, first..elements.len()));
+        }
+        Ok(out)
+    }
+
+    /// Return the JSON text of each chunk.
+    pub fn out(&self) -> Result<Vec<String>, JsonError> {
+        Ok(self.split()?.into_iter().map(|c| c.json).collect())
+    }
+
+    fn chunk(&self, elements: &[Range<usize>], range: Range<usize>) -> JsonChunk {
+        let mut json = String::from("[");
+        for (k, r) in elements[range.clone()].iter().enumerate() {
+            if k > 0 {
+                json.push(',');
+            }
+            json.push_str(&self.input[r.clone()]);
+        }
+        json.push(']');
+        JsonChunk {
+            oversized: json.len() > self.max_bytes,
+            json,
+            elements: range,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_respect_nesting_and_strings() {
+        let s = r#" [ {"a": [1, "]"]}, "x,\"y", 3.5e2 , null, [] ] "#;
+        let v: Vec<_> = json_array_elements(s)
+            .unwrap()
+            .into_iter()
+            .map(|r| &s[r])
+            .collect();
+        assert_eq!(
+            v,
+            vec![r#"{"a": [1, "]"]}"#, r#""x,\"y""#, "3.5e2", "null", "[]"]
+        );
+        assert_eq!(json_array_elements("[]"), Ok(vec![]));
+    }
+
+    #[test]
+    fn rejects_non_arrays_and_malformed_input() {
+        assert_eq!(
+            json_array_elements(r#"{"a": 1}"#),
+            Err(JsonError::NotAnArray)
+        );
+        assert_eq!(json_array_elements("[1, 2"), Err(JsonError::Malformed(5)));
+        assert_eq!(json_array_elements("[1 2]"), Err(JsonError::Malformed(3)));
+        assert_eq!(json_array_elements("[1,}]"), Err(JsonError::Malformed(3)));
+        assert!(json_array_elements("[1] x").is_err());
+        assert_eq!(
+            json_array_elements("[tru, 1]"),
+            Err(JsonError::Malformed(4))
+        );
+        assert_eq!(json_array_elements("[1x]"), Err(JsonError::Malformed(2)));
+        assert!(json_array_elements("[\"a\\q\"]").is_err());
+    }
+
+    #[test]
+    fn packs_elements_under_byte_limit() {
+        let s = r#"[{"id":1}, {"id":2}, {"id":3}, {"id":"a long identifier"}]"#;
+        let chunks = JsonArraySplitter::new(s, 20).split().unwrap();
+        let json: Vec<_> = chunks.iter().map(|c| c.json.as_str()).collect();
+        assert_eq!(
+            json,
+            vec![
+                r#"[{"id":1},{"id":2}]"#,
+                r#"[{"id":3}]"#,
+                r#"[{"id":"a long identifier"}]"#,
+            ]
+        );
+        assert_eq!(chunks[0].elements, 0..2);
+        assert!(!chunks[1].oversized && chunks[2].oversized);
+        assert!(chunks[..2].iter().all(|c| c.json.len() <= 20));
+    }
+}
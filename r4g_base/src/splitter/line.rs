@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use memchr::memchr_iter;
+
+use crate::splitter::utils::{sliding_windows, to_owned_all, window_count, Tail};
+
+/// Returns the byte offset at which every line of `input` starts, followed by `input.len()`.
+///
+/// A line ends after its `\n`; a final line without one still counts. The empty input has no
+/// lines, so the result is just `[0]`. Newlines are found with `memchr`, which skips long lines
+/// many bytes at a time.
+pub fn line_starts(input: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(memchr_iter(b'\n', input.as_bytes()).map(|i| i + 1));
+    if starts.last() != Some(&input.len()) {
+        starts.push(input.len());
+    }
+    starts
+}
+
+/// A splitter for dividing text into windows of whole lines.
+///
+/// - `input`: The input text.
+/// - `n`: Number of lines per window.
+/// - `step`: Step between window starts, in lines (`0` means `step = n`).
+/// - `tail`: How to handle remaining lines at the end (see `Tail`).
+///
+/// Chunks keep the `\n` of every line they contain, so with `step = 0` the chunks concatenate
+/// back to the input.
+pub struct LineSplitter<'a> {
+    input: &'a str,
+    n: usize,
+    step: usize,
+    tail: Tail,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineSplitter<'a> {
+    /// Create a new LineSplitter.
+    pub fn new(input: &'a str, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(input, n, step, keep_tail.into())
+    }
+
+    /// Create a new LineSplitter with an explicit tail policy.
+    pub fn with_tail(input: &'a str, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            input,
+            n,
+            step,
+            tail,
+            starts: line_starts(input),
+        }
+    }
+
+    /// Number of lines in the input.
+    pub fn line_count(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    /// Return the line ranges (0-based, end exclusive) of the windows.
+    fn line_windows(&self) -> impl Iterator<Item = Range<usize>> {
+        sliding_windows(self.line_count(), self.n, self.step, self.tail)
+    }
+
+    /// Return the byte range of each chunk in the input.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        self.line_windows()
+            .map(|l| self.starts[l.start]..self.starts[l.end])
+            .collect()
+    }
+
+    /// Return a vector of borrowed chunks.
+    pub fn split(&self) -> Vec<&'a str> {
+        let mut out = Vec::with_capacity(self.count());
+        out.extend(self.split_indices().into_iter().map(|r| &self.input[r]));
+        out
+    }
+
+    /// Like `split`, with each chunk paired with the 1-based number of its first line in the
+    /// input, e.g. for citing `file.rs:120-140`.
+    pub fn enumerate_split(&self) -> Vec<(usize, &'a str)> {
+        self.line_windows()
+            .map(|l| {
+                (
+                    l.start + 1,
+                    &self.input[self.starts[l.start]..self.starts[l.end]],
+                )
+            })
+            .collect()
+    }
+
+    /// Return the number of chunks without building them.
+    pub fn count(&self) -> usize {
+        window_count(self.line_count(), self.n, self.step, self.tail)
+    }
+
+    /// Return a vector of owned chunks.
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn line_starts_with_and_without_final_newline() {
+        assert_eq!(line_starts(""), vec![0]);
+        assert_eq!(line_starts("a"), vec![0, 1]);
+        assert_eq!(line_starts("a\n"), vec![0, 2]);
+        assert_eq!(line_starts("a\n\nbc"), vec![0, 2, 3, 5]);
+    }
+
+    #[test]
+    fn windows_of_lines() {
+        let s = "one\ntwo\nthree\nfour\nfive";
+        let sp = LineSplitter::new(s, 2, 0, true);
+        assert_eq!(sp.line_count(), 5);
+        assert_eq!(sp.split(), vec!["one\ntwo\n", "three\nfour\n", "five"]);
+        assert_eq!(sp.split().concat(), s);
+        assert_eq!(LineSplitter::new(s, 2, 0, false).count(), 2);
+    }
+
+    #[test]
+    fn enumerate_split_reports_first_line_with_overlap() {
+        let s: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        let numbered = LineSplitter::new(&s, 4, 3, true).enumerate_split();
+        let firsts: Vec<usize> = numbered.iter().map(|&(line, _)| line).collect();
+        assert_eq!(firsts, vec![1, 4, 7, 10]);
+        for (line, text) in numbered {
+            assert!(text.starts_with(&format!("line {line}\n")));
+        }
+        let (line, text) = LineSplitter::new(&s, 4, 3, true).enumerate_split()[1];
+        assert_eq!((line, text), (4, "line 4\nline 5\nline 6\nline 7\n"));
+    }
+
+    proptest! {
+        #[test]
+        fn line_starts_match_naive_split(
+            chars in prop::collection::vec(
+                prop::sample::select(&['a', '\n', 'é', ' ', '😀', '\r', '\u{0a0a}'][..]),
+                0..200,
+            ),
+        ) {
+            let text: String = chars.into_iter().collect();
+            let mut naive = vec![0];
+            let mut at = 0;
+            for line in text.split('\n') {
+                at += line.len() + 1;
+                naive.push(at.min(text.len()));
+            }
+            naive.dedup();
+            prop_assert_eq!(line_starts(&text), naive);
+        }
+    }
+}
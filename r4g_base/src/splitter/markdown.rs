@@ -0,0 +1,412 @@
+use std::ops::Range;
+
+use crate::splitter::from_char::Utf8Splitter;
+use crate::splitter::utils::{back_chars, forward_chars, SplitError, Tail};
+
+/// A chunk of a markdown document together with the heading breadcrumb in effect at its start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownChunk<'a> {
+    /// The chunk text, borrowed from the input.
+    pub text: &'a str,
+    /// Byte range of `text` within the input.
+    pub range: Range<usize>,
+    /// Heading titles from outermost to innermost, e.g. `["Installation", "Linux"]`.
+    pub headings: Vec<&'a str>,
+}
+
+/// A section of the document: the byte range from one heading (or the start) to the next.
+struct Section<'a> {
+    range: Range<usize>,
+    chars: usize,
+    headings: Vec<&'a str>,
+}
+
+/// Parses an ATX heading line (`#` .. `######`), returning its level and trimmed title.
+///
+/// Setext headings (`Title` underlined with `===` / `---`) are deliberately not recognized.
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let level = rest.len() - rest.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &rest[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim();
+    // Optional closing sequence: `## Title ##`.
+    let stripped = title.trim_end_matches('#');
+    let title = if stripped.is_empty() || stripped.ends_with([' ', '\t']) {
+        stripped.trim_end()
+    } else {
+        title
+    };
+    Some((level, title))
+}
+
+/// Parses a fence line (up to 3 spaces, then at least three `` ` `` or `~`), returning the fence
+/// character, its run length, and the remainder of the line (the info string for an opener).
+fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let c = rest.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let run = rest.len() - rest.trim_start_matches(c).len();
+    if run < 3 {
+        return None;
+    }
+    let info = &rest[run..];
+    if c == '`' && info.contains('`') {
+        return None;
+    }
+    Some((c, run, info))
+}
+
+/// Returns the byte ranges of fenced code blocks (`` ``` `` and `~~~`), each spanning from the
+/// start of the opening fence line to the end of the closing fence line.
+///
+/// A block is closed only by a fence of the same character at least as long as the opener with
+/// nothing but whitespace after it; an unterminated block runs to the end of the input.
+pub fn protect_fenced_blocks(input: &str) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut open: Option<(usize, char, usize)> = None;
+    let mut pos = 0usize;
+
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        match (open, fence(trimmed)) {
+            (None, Some((c, run, _))) => open = Some((pos, c, run)),
+            (Some((start, c, run)), Some((fc, frun, info)))
+                if fc == c && frun >= run && info.trim().is_empty() =>
+            {
+                out.push(start..pos + line.len());
+                open = None;
+            }
+            _ => {}
+        }
+        pos += line.len();
+    }
+    if let Some((start, _, _)) = open {
+        out.push(start..input.len());
+    }
+    out
+}
+
+/// Character windows over `range` whose boundaries never fall strictly inside a fenced block.
+///
+/// A window end inside a fence is pulled back to just before the opener, or, if the window
+/// starts at the opener, pushed out to just after the closer (an oversized chunk). An overlap
+/// start inside a fence is pushed forward to just after the closer.
+fn fenced_windows(
+    input: &str,
+    range: Range<usize>,
+    max: usize,
+    overlap: usize,
+    fences: &[Range<usize>],
+) -> Vec<Range<usize>> {
+    let inside = |p: usize| fences.iter().find(|f| f.start < p && p < f.end);
+    let mut out = Vec::new();
+    let mut start = range.start;
+
+    while start < range.end {
+        let mut end = forward_chars(input, start, max, range.end);
+        if let Some(f) = inside(end) {
+            end = if f.start > start {
+                f.start
+            } else {
+                f.end.min(range.end)
+            };
+        }
+        out.push(start..end);
+        if end >= range.end {
+            break;
+        }
+        let mut next = back_chars(input, end, overlap.min(max - 1), start + 1);
+        if let Some(f) = inside(next) {
+            next = f.end;
+        }
+        start = next;
+    }
+    out
+}
+
+/// Splits the input into sections at ATX heading lines, tracking the heading path of each.
+/// Lines inside fenced code blocks are never treated as headings.
+fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut out = Vec::new();
+    let mut stack: Vec<(usize, &str)> = Vec::new();
+    let mut start = 0usize;
+    let mut path: Vec<&str> = Vec::new();
+    let mut pos = 0usize;
+    let fences = protect_fenced_blocks(input);
+
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let in_fence = fences.iter().any(|f| f.contains(&pos));
+        if let Some((level, title)) = atx_heading(trimmed).filter(|_| !in_fence) {
+            if pos > start {
+                out.push(Section {
+                    range: start..pos,
+                    chars: input[start..pos].chars().count(),
+                    headings: path.clone(),
+                });
+            }
+            while stack.last().is_some_and(|&(l, _)| l >= level) {
+                stack.pop();
+            }
+            stack.push((level, title));
+            path = stack.iter().map(|&(_, t)| t).collect();
+            start = pos;
+        }
+        pos += line.len();
+    }
+    if pos > start {
+        out.push(Section {
+            range: start..pos,
+            chars: input[start..pos].chars().count(),
+            headings: path,
+        });
+    }
+    out
+}
+
+/// A splitter for markdown documents that prefers heading boundaries.
+///
+/// - `input`: The markdown source.
+/// - `max_chars`: Maximum number of characters per chunk.
+/// - `overlap`: Characters shared between consecutive windows when an oversized section is
+///   split with `Utf8Splitter`. Merged sections never overlap.
+///
+/// Adjacent sections are merged while they fit in `max_chars`; each chunk carries the heading
+/// path in effect at its start. Only ATX headings are recognized; setext headings are plain text.
+///
+/// With `protect_fences(true)`, no chunk boundary falls inside a fenced code block; a block
+/// larger than `max_chars` becomes its own oversized chunk.
+pub struct MarkdownSplitter<'a> {
+    input: &'a str,
+    max_chars: usize,
+    overlap: usize,
+    protect_fences: bool,
+}
+
+impl<'a> MarkdownSplitter<'a> {
+    pub fn new(input: &'a str, max_chars: usize, overlap: usize) -> Self {
+        Self {
+            input,
+            max_chars,
+            overlap,
+            protect_fences: false,
+        }
+    }
+
+    /// Keep fenced code blocks intact when an oversized section has to be cut.
+    pub fn protect_fences(mut self, protect: bool) -> Self {
+        self.protect_fences = protect;
+        self
+    }
+
+    pub fn split(&self) -> Vec<MarkdownChunk<'a>> {
+        let mut out = Vec::new();
+        self.for_each_range(|range, headings| out.push(self.chunk(range, headings.to_vec())));
+        out
+    }
+
+    /// Return the number of chunks, running the splitting without building them.
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        self.for_each_range(|_, _| count += 1);
+        count
+    }
+
+    fn for_each_range(&self, mut emit: impl FnMut(Range<usize>, &[&'a str])) {
+        let max = self.max_chars.max(1);
+        let step = max.saturating_sub(self.overlap).max(1);
+        let mut pending: Option<(Range<usize>, usize, Vec<&'a str>)> = None;
+        let fences = if self.protect_fences {
+            protect_fenced_blocks(self.input)
+        } else {
+            Vec::new()
+        };
+
+        for sec in sections(self.input) {
+            if sec.chars > max {
+                if let Some((range, _, headings)) = pending.take() {
+                    emit(range, &headings);
+                }
+                if self.protect_fences {
+                    for r in fenced_windows(self.input, sec.range, max, self.overlap, &fences) {
+                        emit(r, &sec.headings);
+                    }
+                    continue;
+                }
+                let base = sec.range.start;
+                let text = &self.input[sec.range];
+                for r in Utf8Splitter::with_tail(text, max, step, Tail::Keep).split_indices() {
+                    emit(base + r.start..base + r.end, &sec.headings);
+                }
+                continue;
+            }
+            pending = match pending.take() {
+                Some((range, chars, headings)) if chars + sec.chars <= max => {
+                    Some((range.start..sec.range.end, chars + sec.chars, headings))
+                }
+                Some((range, _, headings)) => {
+                    emit(range, &headings);
+                    Some((sec.range, sec.chars, sec.headings))
+                }
+                None => Some((sec.range, sec.chars, sec.headings)),
+            };
+        }
+        if let Some((range, _, headings)) = pending {
+            emit(range, &headings);
+        }
+    }
+
+    /// Like `split`, but rejects a zero `max_chars` or an `overlap` of at least `max_chars`
+    /// instead of clamping them.
+    pub fn try_split(&self) -> Result<Vec<MarkdownChunk<'a>>, SplitError> {
+        SplitError::check_overlap(self.max_chars, self.overlap)?;
+        Ok(self.split())
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        self.split()
+            .into_iter()
+            .map(|c| c.text.to_string())
+            .collect()
+    }
+
+    fn chunk(&self, range: Range<usize>, headings: Vec<&'a str>) -> MarkdownChunk<'a> {
+        MarkdownChunk {
+            text: &self.input[range.clone()],
+            range,
+            headings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "Intro text.\n# Installation\nSteps.\n## Linux\napt install.\n## macOS\nbrew install.\n# Usage\nRun it.\n";
+
+    #[test]
+    fn heading_paths() {
+        let chunks = MarkdownSplitter::new(DOC, 25, 0).split();
+        let paths: Vec<_> = chunks.iter().map(|c| c.headings.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![],
+                vec!["Installation"],
+                vec!["Installation", "Linux"],
+                vec!["Installation", "macOS"],
+                vec!["Usage"],
+            ]
+        );
+        for c in &chunks {
+            assert_eq!(&DOC[c.range.clone()], c.text);
+        }
+    }
+
+    #[test]
+    fn merges_small_sections() {
+        let chunks = MarkdownSplitter::new(DOC, 1000, 0).split();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, DOC);
+        assert!(chunks[0].headings.is_empty());
+    }
+
+    #[test]
+    fn oversized_section_falls_back_to_char_windows() {
+        let doc = "# Big\nabcdefghij\n";
+        let chunks = MarkdownSplitter::new(doc, 8, 2).split();
+        assert_eq!(
+            chunks.iter().map(|c| c.text).collect::<Vec<_>>(),
+            vec!["# Big\nab", "abcdefgh", "ghij\n"]
+        );
+        assert!(chunks.iter().all(|c| c.headings == vec!["Big"]));
+    }
+
+    #[test]
+    fn atx_heading_parsing() {
+        assert_eq!(atx_heading("## Title ##"), Some((2, "Title")));
+        assert_eq!(atx_heading("#NoSpace"), None);
+        assert_eq!(atx_heading("####### seven"), None);
+        assert_eq!(atx_heading("    # indented code"), None);
+    }
+
+    #[test]
+    fn setext_headings_are_not_recognized() {
+        let doc = "Title\n=====\nbody\n";
+        let chunks = MarkdownSplitter::new(doc, 5, 0).split();
+        assert!(chunks.iter().all(|c| c.headings.is_empty()));
+    }
+
+    #[test]
+    fn detects_fences_with_info_strings() {
+        let doc = "text\n```rust\nfn main() {}\n```\nmore\n~~~\nx\n~~~\n";
+        let fences = protect_fenced_blocks(doc);
+        assert_eq!(fences.len(), 2);
+        assert_eq!(&doc[fences[0].clone()], "```rust\nfn main() {}\n```\n");
+        assert_eq!(&doc[fences[1].clone()], "~~~\nx\n~~~\n");
+    }
+
+    #[test]
+    fn nested_looking_fences() {
+        let doc = "````md\n```\ninner\n```\n~~~\n````\nafter\n";
+        let fences = protect_fenced_blocks(doc);
+        assert_eq!(fences, vec![0..doc.len() - "after\n".len()]);
+    }
+
+    #[test]
+    fn unterminated_fence_runs_to_eof() {
+        let doc = "intro\n```\n# not a heading\ncode";
+        assert_eq!(protect_fenced_blocks(doc), vec![6..doc.len()]);
+        let chunks = MarkdownSplitter::new(doc, 100, 0).split();
+        assert!(chunks.iter().all(|c| c.headings.is_empty()));
+    }
+
+    #[test]
+    fn boundaries_never_fall_inside_fences() {
+        let doc =
+            "# Code\nSome intro prose here.\n```\nline one\nline two\n```\nTrailing prose after.\n";
+        let fence = protect_fenced_blocks(doc)[0].clone();
+        let chunks = MarkdownSplitter::new(doc, 20, 4)
+            .protect_fences(true)
+            .split();
+        for c in &chunks {
+            assert!(!(fence.start < c.range.start && c.range.start < fence.end));
+            assert!(!(fence.start < c.range.end && c.range.end < fence.end));
+        }
+        assert!(chunks
+            .iter()
+            .any(|c| c.text.starts_with("```") && c.range.end == fence.end));
+        assert_eq!(chunks.last().unwrap().range.end, doc.len());
+    }
+
+    #[test]
+    fn oversized_fence_is_its_own_chunk() {
+        let doc = "ab\n```\n0123456789\n```\ncd\n";
+        let chunks = MarkdownSplitter::new(doc, 8, 0).protect_fences(true).out();
+        assert_eq!(chunks, vec!["ab\n", "```\n0123456789\n```\n", "cd\n"]);
+    }
+
+    #[test]
+    fn count_matches_split() {
+        for max in [5, 25, 200] {
+            let sp = MarkdownSplitter::new(DOC, max, 2);
+            assert_eq!(sp.count(), sp.split().len());
+            let sp = sp.protect_fences(true);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+}
@@ -1,4 +1,68 @@
 pub mod utils;
+pub mod chunk;
 pub mod from_char;
-mod from_list;
+pub mod from_list;
+pub mod from_bytes;
+pub mod from_reader;
+pub mod grid;
 pub mod to_list;
+pub mod markdown;
+pub mod html_aware;
+pub mod code;
+pub mod line;
+pub mod normalize;
+pub mod token;
+pub mod reassemble;
+pub mod owning;
+pub mod smart;
+pub mod sentence;
+pub mod natural;
+pub mod ext;
+pub mod traits;
+pub mod preprocess;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "unicode-width")]
+pub mod width;
+
+pub use chunk::{chunk_id, ArcChunk, Chunk, SerializableChunk, SharedSlice};
+pub use code::CodeSplitter;
+pub use ext::StrSplitExt;
+pub use from_bytes::BytesSplitter;
+pub use from_char::{chunk, split_many, IndexedStr, SplitStats, SplitterConfig, Utf8Splitter};
+pub use from_list::{
+    split_slice_by, window_pieces, zip_split, SliceSplitter, SliceWindow, SliceWindows,
+    ZippedWindows,
+};
+pub use from_reader::{
+    ByteStreamSplitter, CharIterSplitter, ReaderSplitter, StreamingUtf8Splitter,
+};
+pub use grid::{GridBlock, GridSplitter};
+pub use html_aware::HtmlAwareSplitter;
+pub use line::LineSplitter;
+pub use markdown::{MarkdownChunk, MarkdownSplitter};
+pub use natural::NaturalTextSplitter;
+pub use owning::{split_owned, OwningSplitter, SliceSplitterOwned, Utf8SplitterOwned};
+pub use preprocess::{PreProcessor, PreStep, Preprocessed};
+pub use reassemble::{reassemble, reconstruct, verify_lossless, ReassemblyError};
+pub use sentence::{SentenceSplitter, SentenceStream, SentenceWindow, SentenceWindowSplitter};
+pub use smart::SmartSplitter;
+pub use token::{TokenChunk, TokenSplitter};
+pub use traits::{DynSplitter, Splitter};
+pub use utils::{
+    coverage, hash_chunk, nth_window, sliding_windows, sliding_windows_sampled, split_into_k,
+    stable_hash, to_owned_all, window_count, SeparatorPolicy, SplitError, SplitWarning,
+    StableHasher, Tail, TailPolicy, ToOwnedChunks, TrimPolicy,
+};
+#[cfg(feature = "tokio")]
+pub use from_reader::{split_reader, AsyncReaderSplitter};
+#[cfg(feature = "html")]
+pub use html::HtmlSplitter;
+#[cfg(feature = "json")]
+pub use json::{JsonArraySplitter, JsonChunk, JsonError};
+#[cfg(feature = "unicode")]
+pub use sentence::unicode_sentence_spans;
+#[cfg(feature = "unicode-width")]
+pub use width::{char_width, str_width};
@@ -0,0 +1,265 @@
+use std::ops::Range;
+
+use crate::splitter::sentence::sentence_spans;
+use crate::splitter::utils::{forward_chars, to_owned_all, SplitError};
+
+/// Words that end in a period without ending the sentence, compared lowercased and without the
+/// trailing period.
+const ABBREVIATIONS: &[&str] = &[
+    "approx", "cf", "dr", "e.g", "etc", "fig", "i.e", "inc", "jr", "ltd", "mr", "mrs", "ms",
+    "prof", "sr", "st", "vs",
+];
+
+/// A splitter for natural-language text that packs whole sentences into chunks of at most
+/// `max_chars` characters, falling back to whole words for a sentence that does not fit on its
+/// own, and to hard cuts for a word that does not.
+///
+/// - `input`: The input text.
+/// - `max_chars`: Maximum number of characters per chunk.
+/// - `overlap_chars`: Maximum number of characters repeated from the end of one chunk at the
+///   start of the next. Only whole sentences (or words, or cut pieces) are repeated, so the
+///   overlap can be shorter, down to none.
+///
+/// Sentences come from `sentence_spans`, with a period after a common abbreviation ("Dr.",
+/// "e.g.") or before a lowercase word not taken as a sentence end. Chunks are borrowed from the
+/// input and do not start or end with whitespace.
+pub struct NaturalTextSplitter<'a> {
+    input: &'a str,
+    max_chars: usize,
+    overlap_chars: usize,
+}
+
+impl<'a> NaturalTextSplitter<'a> {
+    pub fn new(input: &'a str, max_chars: usize, overlap_chars: usize) -> Self {
+        Self {
+            input,
+            max_chars,
+            overlap_chars,
+        }
+    }
+
+    /// Return the byte range of each sentence, with the abbreviation handling described on the
+    /// type.
+    pub fn sentence_indices(&self) -> Vec<Range<usize>> {
+        let mut out: Vec<Range<usize>> = Vec::new();
+        for span in sentence_spans(self.input) {
+            match out.last_mut() {
+                Some(prev) if self.continues(prev.clone(), span.start) => prev.end = span.end,
+                _ => out.push(span),
+            }
+        }
+        out
+    }
+
+    /// Whether the sentence starting at `next` continues the one at `prev`, whose final period
+    /// was only an abbreviation or is followed by a lowercase word.
+    fn continues(&self, prev: Range<usize>, next: usize) -> bool {
+        let Some(body) = self.input[prev].strip_suffix('.') else {
+            return false;
+        };
+        let last_word = body.rsplit(char::is_whitespace).next().unwrap_or(body);
+        let last_word = last_word.trim_start_matches(['(', '"', '\'', '“', '‘']);
+        ABBREVIATIONS.contains(&last_word.to_lowercase().as_str())
+            || self.input[next..].starts_with(char::is_lowercase)
+    }
+
+    /// The pieces chunks are built from: sentences of at most `max_chars` characters, the words
+    /// of longer sentences, and `max_chars`-character cuts of longer words, in input order.
+    fn units(&self, max: usize) -> Vec<Range<usize>> {
+        let input = self.input;
+        let mut units = Vec::new();
+        for sentence in self.sentence_indices() {
+            if input[sentence.clone()].chars().count() <= max {
+                units.push(sentence);
+                continue;
+            }
+            for word in word_ranges(input, sentence) {
+                let mut start = word.start;
+                while start < word.end {
+                    let cut = forward_chars(input, start, max, word.end);
+                    units.push(start..cut);
+                    start = cut;
+                }
+            }
+        }
+        units
+    }
+
+    /// Return the byte range of each chunk.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        let max = self.max_chars.max(1);
+        let units = self.units(max);
+        let offsets: Vec<usize> = self.input.char_indices().map(|(i, _)| i).collect();
+        let chars = |r: Range<usize>| {
+            offsets.partition_point(|&o| o < r.end) - offsets.partition_point(|&o| o < r.start)
+        };
+
+        let mut out = Vec::new();
+        let mut first = 0usize;
+        while first < units.len() {
+            let start = units[first].start;
+            let mut last = first;
+            while last + 1 < units.len() && chars(start..units[last + 1].end) <= max {
+                last += 1;
+            }
+            let end = units[last].end;
+            out.push(start..end);
+
+            // Restart at the earliest later unit that fits in the overlap and still leaves room
+            // for the unit after this chunk, so every chunk adds something new.
+            let Some(next) = units.get(last + 1) else {
+                break;
+            };
+            first = (first + 1..=last)
+                .find(|&j| {
+                    chars(units[j].start..end) <= self.overlap_chars
+                        && chars(units[j].start..next.end) <= max
+                })
+                .unwrap_or(last + 1);
+        }
+        out
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+
+    /// Like `split`, but rejects a zero `max_chars` or an `overlap_chars` of at least `max_chars`
+    /// instead of clamping them.
+    pub fn try_split(&self) -> Result<Vec<&'a str>, SplitError> {
+        SplitError::check_overlap(self.max_chars, self.overlap_chars)?;
+        Ok(self.split())
+    }
+
+    /// Return the number of chunks.
+    pub fn count(&self) -> usize {
+        self.split_indices().len()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+/// Byte ranges of the whitespace-separated words of `input[range]`.
+fn word_ranges(input: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in input[range.clone()].char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                out.push(range.start + s..range.start + i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        out.push(range.start + s..range.end);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_whole_sentences() {
+        let s = "One two. Three four. Five six.";
+        assert_eq!(
+            NaturalTextSplitter::new(s, 20, 0).split(),
+            vec!["One two. Three four.", "Five six."]
+        );
+        assert_eq!(NaturalTextSplitter::new(s, 100, 0).split(), vec![s]);
+        assert!(NaturalTextSplitter::new("  ", 10, 0).split().is_empty());
+    }
+
+    #[test]
+    fn abbreviations_do_not_end_sentences() {
+        let s = "Mr. Smith went home. Fine. See e.g. the docs! It works.";
+        assert_eq!(
+            NaturalTextSplitter::new(s, 100, 0).sentence_indices().len(),
+            4
+        );
+        assert_eq!(
+            NaturalTextSplitter::new("Mr. Smith went home. Fine.", 17, 0).split(),
+            vec!["Mr. Smith went", "home. Fine."]
+        );
+        assert_eq!(
+            NaturalTextSplitter::new("It ended. the end.", 100, 0)
+                .sentence_indices()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn falls_back_to_words_then_chars() {
+        let s = "a supercalifragilistic word";
+        assert_eq!(
+            NaturalTextSplitter::new(s, 8, 0).split(),
+            vec!["a", "supercal", "ifragili", "stic", "word"]
+        );
+        assert_eq!(
+            NaturalTextSplitter::new("añb😀 cdé", 2, 0).split(),
+            vec!["añ", "b😀", "cd", "é"]
+        );
+    }
+
+    #[test]
+    fn overlap_repeats_whole_units() {
+        let s = "One. Two. Three. Four.";
+        assert_eq!(
+            NaturalTextSplitter::new(s, 12, 5).split(),
+            vec!["One. Two.", "Two. Three.", "Four."]
+        );
+        assert_eq!(
+            NaturalTextSplitter::new(s, 12, 5)
+                .try_split()
+                .map(|v| v.len()),
+            Ok(3)
+        );
+        assert_eq!(
+            NaturalTextSplitter::new(s, 4, 4).try_split(),
+            Err(SplitError::OverlapTooLarge {
+                overlap: 4,
+                size: 4
+            })
+        );
+    }
+
+    #[test]
+    fn chunks_fit_and_progress() {
+        let text = "Dr. Who e.g. arrived.  The quick brown fox jumps over the lazy dog! \
+                    Zwölf Boxkämpfer jagen Viktor quer über den großen Sylter Deich? \
+                    Pneumonoultramicroscopicsilicovolcanoconiosis is long. Short one.";
+        for max in [1, 3, 7, 16, 40, 200] {
+            for overlap in [0, 2, 10, 50] {
+                let sp = NaturalTextSplitter::new(text, max, overlap);
+                let ranges = sp.split_indices();
+                assert_eq!(sp.count(), ranges.len());
+                assert!(ranges.windows(2).all(|w| w[0].start < w[1].start));
+                assert!(ranges.windows(2).all(|w| w[0].end < w[1].end));
+                for r in &ranges {
+                    let chunk = &text[r.clone()];
+                    assert!(chunk.chars().count() <= max, "{max} {chunk:?}");
+                    assert_eq!(chunk, chunk.trim());
+                }
+                let words: usize = text.split_whitespace().count();
+                if overlap == 0 && max >= 45 {
+                    let joined: usize = sp
+                        .split()
+                        .iter()
+                        .map(|c| c.split_whitespace().count())
+                        .sum();
+                    assert_eq!(joined, words);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::splitter::from_char::utf8_by_chars_indices;
+use crate::splitter::utils::{is_combining_mark, Tail};
+
+/// A chunk of normalized text together with the byte range it came from in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedChunk {
+    /// The chunk text, after normalization.
+    pub text: String,
+    /// Byte range of the un-normalized input that produced `text`.
+    pub source: Range<usize>,
+}
+
+/// Whether `c` attaches to the preceding character for normalization purposes.
+///
+/// Covers the combining diacritical mark blocks, the kana voicing marks and the Hangul medial and
+/// final jamo. This is an approximation of "non-starter", not the full Unicode property.
+fn is_combining(c: char) -> bool {
+    is_combining_mark(c) || matches!(c, '\u{3099}'..='\u{309A}' | '\u{1161}'..='\u{11FF}')
+}
+
+/// Whether a cluster can start at `c` under NFC: `c` is a starter that never composes with
+/// the character before it (canonical combining class 0 and `NFC_Quick_Check=Yes`), so the NFC
+/// of the text is the NFC of each cluster, concatenated.
+#[cfg(feature = "unicode-normalization")]
+fn is_nfc_boundary(c: char) -> bool {
+    use unicode_normalization::char::canonical_combining_class;
+    use unicode_normalization::{is_nfc_quick, IsNormalized};
+
+    canonical_combining_class(c) == 0 && is_nfc_quick(std::iter::once(c)) == IsNormalized::Yes
+}
+
+/// Normalizes `input` cluster by cluster and windows the result by character count, mapping every
+/// chunk back to the byte range of the original text it was produced from.
+///
+/// - `input`: The un-normalized input.
+/// - `n`: The number of normalized characters per chunk.
+/// - `step`: Step between window starts (`0` means `step = n`).
+/// - `tail`: How to handle remaining characters at the end (`bool` or `Tail`).
+/// - `normalize`: The normalization to apply to each cluster, e.g. case folding or stripping
+///   marks.
+///
+/// A cluster is a character plus the combining marks that follow it (see `is_combining`), so
+/// `normalize` never sees a character apart from its marks. This is not enough for NFC, which can
+/// also compose across starters such as Hangul jamo; use `nfc_then_split` for that. The source
+/// range of a chunk is widened to whole clusters, so it may be slightly larger than the chunk
+/// when a window cuts a cluster.
+pub fn normalize_then_split<F>(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+    normalize: F,
+) -> Vec<NormalizedChunk>
+where
+    F: for<'s> Fn(&'s str) -> Cow<'s, str>,
+{
+    split_clusters(input, n, step, tail.into(), is_combining, normalize)
+}
+
+/// Normalizes `input` to NFC and windows the result by character count, mapping every chunk back
+/// to the byte range of the original text it was produced from.
+///
+/// - `input`: The un-normalized input.
+/// - `n`: The number of NFC characters per chunk.
+/// - `step`: Step between window starts (`0` means `step = n`).
+/// - `tail`: How to handle remaining characters at the end (`bool` or `Tail`).
+///
+/// Precomposed and decomposed spellings of the same text give the same chunks. The text is
+/// normalized between NFC-stable boundaries, so the result is the NFC of the whole input and
+/// each source range covers whole compositions; it may be slightly larger than the chunk when a
+/// window cuts one.
+#[cfg(feature = "unicode-normalization")]
+pub fn nfc_then_split(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: impl Into<Tail>,
+) -> Vec<NormalizedChunk> {
+    use unicode_normalization::UnicodeNormalization;
+
+    split_clusters(
+        input,
+        n,
+        step,
+        tail.into(),
+        |c| !is_nfc_boundary(c),
+        |s| Cow::Owned(s.nfc().collect()),
+    )
+}
+
+/// Normalizes `input` one cluster at a time, a cluster ending before every character for which
+/// `extends` is false, and windows the result with the source range of each chunk.
+fn split_clusters(
+    input: &str,
+    n: usize,
+    step: usize,
+    tail: Tail,
+    extends: impl Fn(char) -> bool,
+    normalize: impl for<'s> Fn(&'s str) -> Cow<'s, str>,
+) -> Vec<NormalizedChunk> {
+    let mut normalized = String::with_capacity(input.len());
+    // (normalized offset, original offset) at each cluster start, plus the end of both strings.
+    let mut map: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0usize;
+    for (i, c) in input.char_indices().skip(1) {
+        if !extends(c) {
+            map.push((normalized.len(), start));
+            normalized.push_str(&normalize(&input[start..i]));
+            start = i;
+        }
+    }
+    if start < input.len() {
+        map.push((normalized.len(), start));
+        normalized.push_str(&normalize(&input[start..]));
+    }
+    map.push((normalized.len(), input.len()));
+
+    utf8_by_chars_indices(&normalized, n, step, tail)
+        .into_iter()
+        .map(|r| {
+            let first = map.partition_point(|&(norm, _)| norm <= r.start) - 1;
+            let last = map
+                .partition_point(|&(norm, _)| norm < r.end)
+                .max(first + 1);
+            NormalizedChunk {
+                text: normalized[r].to_string(),
+                source: map[first].1..map[last].1,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "unicode-normalization")]
+    fn texts(chunks: Vec<NormalizedChunk>) -> Vec<String> {
+        chunks.into_iter().map(|c| c.text).collect()
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn decomposed_and_precomposed_give_same_chunks() {
+        let decomposed = "cafe\u{0301} a\u{0300} la";
+        let precomposed = "café à la";
+        let a = texts(nfc_then_split(decomposed, 4, 0, true));
+        assert_eq!(a, vec!["café", " à l", "a"]);
+        assert_eq!(a, texts(nfc_then_split(precomposed, 4, 0, true)));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn source_ranges_point_into_original() {
+        let input = "cafe\u{0301} a\u{0300} la";
+        let chunks = nfc_then_split(input, 4, 0, true);
+        let sources: Vec<_> = chunks.iter().map(|c| &input[c.source.clone()]).collect();
+        assert_eq!(sources, vec!["cafe\u{0301}", " a\u{0300} l", "a"]);
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn hangul_jamo_compose_into_syllables() {
+        // 한국 spelled as conjoining jamo, then as a syllable plus a trailing jamo.
+        let jamo = "\u{1112}\u{1161}\u{11AB}\u{1100}\u{116E}\u{11A8}";
+        let mixed = "\u{D55C}\u{AD6C}\u{11A8}";
+        for input in [jamo, mixed, "한국"] {
+            let chunks = nfc_then_split(input, 1, 0, true);
+            assert_eq!(texts(chunks.clone()), vec!["한", "국"], "{input:?}");
+            let sources: Vec<_> = chunks.iter().map(|c| c.source.clone()).collect();
+            assert_eq!(sources[0].start, 0);
+            assert_eq!(sources[0].end, sources[1].start);
+            assert_eq!(sources[1].end, input.len());
+        }
+        let chunks = nfc_then_split(jamo, 1, 0, true);
+        assert_eq!(&jamo[chunks[0].source.clone()], "\u{1112}\u{1161}\u{11AB}");
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn stacked_marks_are_reordered_and_composed() {
+        // Dot below (ccc 220) and circumflex (ccc 230) in either order compose to U+1EAD; the
+        // extra acute stays a combining mark of the same cluster.
+        for input in ["xa\u{0323}\u{0302}y", "xa\u{0302}\u{0323}y", "x\u{1EAD}y"] {
+            assert_eq!(
+                texts(nfc_then_split(input, 1, 0, true)),
+                vec!["x", "\u{1EAD}", "y"]
+            );
+        }
+        let input = "a\u{0302}\u{0323}\u{0301}b";
+        let chunks = nfc_then_split(input, 1, 0, true);
+        assert_eq!(texts(chunks.clone()), vec!["\u{1EAD}", "\u{0301}", "b"]);
+        // Both characters of the composed cluster map to the whole decomposed cluster.
+        assert_eq!(chunks[0].source, 0..7);
+        assert_eq!(chunks[1].source, 0..7);
+        assert_eq!(chunks[2].source, 7..8);
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn chunks_concatenate_to_nfc_of_whole_input() {
+        use unicode_normalization::UnicodeNormalization;
+
+        let input = "e\u{0301}\u{1100}\u{1161}\u{11A8} A\u{030A}\u{212B} \u{0CC6}\u{0CC2}\u{0CD5}o\u{0308}\u{0304}";
+        let whole: String = input.nfc().collect();
+        for n in [1, 2, 3, 5] {
+            let got: String = texts(nfc_then_split(input, n, 0, true)).concat();
+            assert_eq!(got, whole, "n={n}");
+        }
+    }
+
+    #[test]
+    fn custom_normalization_sees_whole_clusters() {
+        let input = "Cafe\u{0301} À";
+        fn strip(s: &str) -> Cow<'_, str> {
+            Cow::Owned(s.chars().take(1).collect())
+        }
+        let chunks = normalize_then_split(input, 2, 0, true, strip);
+        let texts: Vec<_> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["Ca", "fe", " À"]);
+        assert_eq!(&input[chunks[1].source.clone()], "fe\u{0301}");
+    }
+
+    #[test]
+    fn identity_normalization_maps_exactly() {
+        let input = "a😀bc";
+        for c in normalize_then_split(input, 2, 1, false, |s| Cow::Borrowed(s)) {
+            assert_eq!(&input[c.source.clone()], c.text);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::ops::Range;
+
+use crate::splitter::from_char::{utf8_by_chars_indices, Utf8Splitter};
+use crate::splitter::from_list::SliceSplitter;
+use crate::splitter::utils::{SplitError, Tail};
+
+/// A document together with its chunks, as one owned value.
+///
+/// The splitter owns the `String` and stores chunk byte ranges instead of slices, so it has no
+/// borrow of its own data; the `&str` chunks it hands out borrow from `self`. This lets a
+/// function return "a document plus its chunks" without a self-referential struct.
+pub struct OwningSplitter {
+    text: String,
+    ranges: Vec<Range<usize>>,
+}
+
+impl OwningSplitter {
+    /// Split `text` into windows of `n` characters, like `Utf8Splitter::with_tail`.
+    pub fn new(text: String, n: usize, step: usize, tail: Tail) -> Self {
+        let ranges = utf8_by_chars_indices(&text, n, step, tail);
+        Self { text, ranges }
+    }
+
+    /// Split `text` with any splitter that reports byte ranges, e.g.
+    /// `|s| Utf8Splitter::new(s, 100, 80, true).trim(TrimPolicy::Both).split_indices()`.
+    ///
+    /// Panics if a range is out of bounds or not on char boundaries.
+    pub fn with_ranges(text: String, split: impl FnOnce(&str) -> Vec<Range<usize>>) -> Self {
+        let ranges = split(&text);
+        for r in &ranges {
+            assert!(text.get(r.clone()).is_some(), "invalid chunk range {r:?}");
+        }
+        Self { text, ranges }
+    }
+
+    /// The source text.
+    pub fn source(&self) -> &str {
+        &self.text
+    }
+
+    /// The byte range of each chunk within `source`.
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.ranges.get(index).map(|r| &self.text[r.clone()])
+    }
+
+    /// Iterate over the chunks, borrowing from `self`.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.ranges.iter().map(|r| &self.text[r.clone()])
+    }
+
+    /// Give back the source text and the chunk ranges.
+    pub fn into_parts(self) -> (String, Vec<Range<usize>>) {
+        (self.text, self.ranges)
+    }
+}
+
+/// A `Utf8Splitter` that owns its input `String`, so it can be stored next to other data or sent
+/// to another thread once the original text is gone.
+///
+/// The settings are kept in a `Utf8Splitter` and applied to the owned text on every call (see
+/// `splitter`), so the results are exactly those of the borrowed splitter. Chunks borrow from
+/// `self`.
+pub struct Utf8SplitterOwned {
+    text: String,
+    config: Utf8Splitter<'static>,
+}
+
+impl Utf8SplitterOwned {
+    pub fn new(text: String, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(text, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(text: String, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            text,
+            config: Utf8Splitter::with_tail("", n, step, tail),
+        }
+    }
+
+    /// Change the settings with the `Utf8Splitter` builder methods, e.g.
+    /// `.configure(|s| s.trim(TrimPolicy::Both).skip_empty(true))`.
+    pub fn configure(
+        mut self,
+        f: impl FnOnce(Utf8Splitter<'static>) -> Utf8Splitter<'static>,
+    ) -> Self {
+        self.config = f(self.config);
+        self
+    }
+
+    /// A borrowed splitter over the owned text, for the rest of the `Utf8Splitter` API.
+    pub fn splitter(&self) -> Utf8Splitter<'_> {
+        self.config.config().splitter(&self.text)
+    }
+
+    /// The source text.
+    pub fn source(&self) -> &str {
+        &self.text
+    }
+
+    pub fn split(&self) -> Vec<&str> {
+        self.splitter().split()
+    }
+
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        self.splitter().split_indices()
+    }
+
+    /// Iterate over the chunks, borrowing from `self`.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.split_indices().into_iter().map(|r| &self.text[r])
+    }
+
+    pub fn count(&self) -> usize {
+        self.splitter().count()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        self.splitter().out()
+    }
+
+    /// Give back the source text.
+    pub fn into_parts(self) -> String {
+        self.text
+    }
+}
+
+/// A `SliceSplitter` that owns its input `Vec`; see `Utf8SplitterOwned`.
+pub struct SliceSplitterOwned<T> {
+    items: Vec<T>,
+    n: usize,
+    step: usize,
+    tail: Tail,
+}
+
+impl<T> SliceSplitterOwned<T> {
+    pub fn new(items: Vec<T>, n: usize, step: usize, keep_tail: bool) -> Self {
+        Self::with_tail(items, n, step, keep_tail.into())
+    }
+
+    pub fn with_tail(items: Vec<T>, n: usize, step: usize, tail: Tail) -> Self {
+        Self {
+            items,
+            n,
+            step,
+            tail,
+        }
+    }
+
+    /// A borrowed splitter over the owned items, for the rest of the `SliceSplitter` API.
+    pub fn splitter(&self) -> SliceSplitter<'_, T> {
+        SliceSplitter::with_tail(&self.items, self.n, self.step, self.tail)
+    }
+
+    /// The source items.
+    pub fn source(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn split(&self) -> Vec<&[T]> {
+        self.splitter().split()
+    }
+
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        self.splitter().split_indices()
+    }
+
+    /// Iterate over the sub-slices, borrowing from `self`.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> + '_ {
+        self.split_indices().into_iter().map(|r| &self.items[r])
+    }
+
+    pub fn count(&self) -> usize {
+        self.splitter().count()
+    }
+
+    pub fn out(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.splitter().out()
+    }
+
+    /// Give back the source items.
+    pub fn into_parts(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Move the items into one vector per window, without cloning them. Each window vector has
+    /// exactly its window's capacity; items between windows (`step > n`) are dropped.
+    ///
+    /// Returns `SplitError::OverlappingWindows` if a window overlaps the one before it (`step`
+    /// smaller than `n`, or a `Tail::PadBackward` tail), since an item can only be moved once.
+    /// Use `out` for those configurations. On error the items are dropped.
+    pub fn into_windows(self) -> Result<Vec<Vec<T>>, SplitError> {
+        let ranges = self.split_indices();
+        if let Some(index) = (1..ranges.len()).find(|&i| ranges[i].start < ranges[i - 1].end) {
+            return Err(SplitError::OverlappingWindows { index });
+        }
+        let mut items = self.items.into_iter();
+        let mut pos = 0;
+        let mut out = Vec::with_capacity(ranges.len());
+        for r in ranges {
+            items.by_ref().take(r.start - pos).for_each(drop);
+            let mut window = Vec::with_capacity(r.len());
+            window.extend(items.by_ref().take(r.len()));
+            pos = r.end;
+            out.push(window);
+        }
+        Ok(out)
+    }
+}
+
+/// Move `input` into consecutive windows of `n` items, without cloning them; the last, shorter
+/// window is kept only if `keep_tail`. Each window vector has exactly its window's capacity.
+///
+/// For overlapping windows, which need `T: Clone`, see `SliceSplitter::out`.
+pub fn split_owned<T>(input: Vec<T>, n: usize, keep_tail: bool) -> Vec<Vec<T>> {
+    // Non-overlapping windows always pass the overlap check.
+    SliceSplitterOwned::new(input, n, 0, keep_tail)
+        .into_windows()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::splitter::from_char::Utf8Splitter;
+    use crate::splitter::utils::TrimPolicy;
+
+    fn load(n: usize) -> OwningSplitter {
+        let text = String::from("a😀bcdéfg");
+        OwningSplitter::new(text, n, 0, Tail::Keep)
+    }
+
+    #[test]
+    fn returns_document_with_chunks() {
+        let doc = load(3);
+        assert_eq!(doc.iter().collect::<Vec<_>>(), vec!["a😀b", "cdé", "fg"]);
+        assert_eq!(doc.get(1), Some("cdé"));
+        assert_eq!(doc.get(3), None);
+        assert_eq!(doc.len(), 3);
+    }
+
+    #[test]
+    fn with_ranges_accepts_any_configuration() {
+        let doc = OwningSplitter::with_ranges(String::from(" ab  cd "), |s| {
+            Utf8Splitter::new(s, 4, 0, true)
+                .trim(TrimPolicy::Both)
+                .split_indices()
+        });
+        assert_eq!(doc.iter().collect::<Vec<_>>(), vec!["ab", "cd"]);
+        let (text, ranges) = doc.into_parts();
+        assert_eq!(&text[ranges[1].clone()], "cd");
+    }
+
+    #[test]
+    fn owned_splitter_matches_borrowed_and_moves_across_threads() {
+        let text = String::from(" añ  cdé fg ");
+        let expected = Utf8Splitter::new(&text, 3, 2, true)
+            .trim(TrimPolicy::Both)
+            .skip_empty(true)
+            .out();
+        let owned = Utf8SplitterOwned::new(text.clone(), 3, 2, true)
+            .configure(|s| s.trim(TrimPolicy::Both).skip_empty(true));
+        let (owned, out) = std::thread::spawn(move || {
+            let out = owned.out();
+            (owned, out)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(out, expected);
+        assert_eq!(owned.iter().collect::<Vec<_>>(), owned.split());
+        assert_eq!(owned.count(), expected.len());
+        assert_eq!(owned.into_parts(), text);
+    }
+
+    #[test]
+    fn owned_slice_splitter() {
+        let owned = SliceSplitterOwned::new((0..7).collect::<Vec<u8>>(), 3, 2, false);
+        assert_eq!(owned.split(), vec![&[0, 1, 2][..], &[2, 3, 4], &[4, 5, 6]]);
+        assert_eq!(owned.iter().count(), owned.count());
+        assert_eq!(owned.out()[1], vec![2, 3, 4]);
+        assert_eq!(owned.into_parts().len(), 7);
+    }
+
+    /// Neither `Clone` nor `Copy`, so windows of it can only be made by moving.
+    #[derive(Debug, PartialEq)]
+    struct Token(String);
+
+    #[test]
+    fn split_owned_moves_items() {
+        let tokens =
+            |r: std::ops::Range<usize>| r.map(|i| Token(i.to_string())).collect::<Vec<_>>();
+        let windows = split_owned(tokens(0..7), 3, true);
+        assert_eq!(windows, vec![tokens(0..3), tokens(3..6), tokens(6..7)]);
+        assert_eq!(windows[2].capacity(), 1);
+        assert_eq!(split_owned(tokens(0..7), 3, false).len(), 2);
+        assert!(split_owned(tokens(0..7), 0, true).is_empty());
+
+        let gapped = SliceSplitterOwned::new(tokens(0..8), 2, 3, true).into_windows();
+        assert_eq!(gapped, Ok(vec![tokens(0..2), tokens(3..5), tokens(6..8)]));
+    }
+
+    #[test]
+    fn into_windows_rejects_overlap() {
+        let items: Vec<Token> = (0..5).map(|i| Token(i.to_string())).collect();
+        assert_eq!(
+            SliceSplitterOwned::new(items, 3, 2, true).into_windows(),
+            Err(SplitError::OverlappingWindows { index: 1 })
+        );
+        let items: Vec<Token> = (0..6).map(|i| Token(i.to_string())).collect();
+        let exact = SliceSplitterOwned::with_tail(items, 3, 0, Tail::PadBackward);
+        assert_eq!(exact.into_windows().map(|w| w.len()), Ok(2));
+        let items: Vec<Token> = (0..5).map(|i| Token(i.to_string())).collect();
+        assert_eq!(
+            SliceSplitterOwned::with_tail(items, 3, 0, Tail::PadBackward).into_windows(),
+            Err(SplitError::OverlappingWindows { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use crate::splitter::from_char::Utf8Splitter;
+
+/// One rewrite applied to the text before splitting (see `PreProcessor`).
+pub enum PreStep {
+    /// Replace every run of whitespace (line breaks included) with a single space.
+    CollapseWhitespace,
+    /// Remove control characters other than `\n`, `\r` and `\t`.
+    StripControl,
+    /// Replace curly single and double quotes (`‘ ’ ‚ ‛ “ ” „ ‟`) with `'` and `"`.
+    ReplaceSmartQuotes,
+    /// Any other rewrite. Offsets cannot be traced through it, so after a `Custom` step
+    /// `Preprocessed::original_range` returns `None`.
+    Custom(Box<dyn Fn(&str) -> String>),
+}
+
+/// An ordered list of `PreStep`s run over the raw input before it is split.
+///
+/// Chunk offsets from a splitter over the processed text refer to the processed text; use
+/// `Preprocessed::original_range` to cite the raw input instead.
+pub struct PreProcessor {
+    steps: Vec<PreStep>,
+}
+
+impl PreProcessor {
+    pub fn new(steps: Vec<PreStep>) -> Self {
+        Self { steps }
+    }
+
+    /// Run the steps over `raw` in order.
+    pub fn apply(&self, raw: &str) -> Preprocessed {
+        let mut text = raw.to_string();
+        // (processed offset, raw offset) at each processed char start, plus the end of both.
+        let mut map: Option<Vec<(usize, usize)>> = Some(identity_map(raw));
+        for step in &self.steps {
+            let (next, anchors) = match step {
+                PreStep::CollapseWhitespace => {
+                    let mut in_space = false;
+                    map_chars(&text, |c| {
+                        let space = c.is_whitespace();
+                        let keep = !(space && in_space);
+                        in_space = space;
+                        keep.then_some(if space { ' ' } else { c })
+                    })
+                }
+                PreStep::StripControl => map_chars(&text, |c| {
+                    (!c.is_control() || matches!(c, '\n' | '\r' | '\t')).then_some(c)
+                }),
+                PreStep::ReplaceSmartQuotes => map_chars(&text, |c| {
+                    Some(match c {
+                        '\u{2018}'..='\u{201B}' => '\'',
+                        '\u{201C}'..='\u{201F}' => '"',
+                        c => c,
+                    })
+                }),
+                PreStep::Custom(f) => {
+                    text = f(&text);
+                    map = None;
+                    continue;
+                }
+            };
+            map = map.map(|prev| {
+                anchors
+                    .into_iter()
+                    .map(|(out, inp)| (out, lookup(&prev, inp).unwrap_or(raw.len())))
+                    .collect()
+            });
+            text = next;
+        }
+        Preprocessed { text, map }
+    }
+}
+
+fn identity_map(s: &str) -> Vec<(usize, usize)> {
+    let mut map: Vec<_> = s.char_indices().map(|(i, _)| (i, i)).collect();
+    map.push((s.len(), s.len()));
+    map
+}
+
+/// Rewrites `input` one char at a time (`None` drops the char), returning the result and a
+/// `(output offset, input offset)` anchor for each output char plus the end.
+fn map_chars(
+    input: &str,
+    mut f: impl FnMut(char) -> Option<char>,
+) -> (String, Vec<(usize, usize)>) {
+    let mut out = String::with_capacity(input.len());
+    let mut anchors = Vec::new();
+    for (i, c) in input.char_indices() {
+        if let Some(d) = f(c) {
+            anchors.push((out.len(), i));
+            out.push(d);
+        }
+    }
+    anchors.push((out.len(), input.len()));
+    (out, anchors)
+}
+
+fn lookup(map: &[(usize, usize)], offset: usize) -> Option<usize> {
+    map.binary_search_by_key(&offset, |&(p, _)| p)
+        .ok()
+        .map(|k| map[k].1)
+}
+
+/// Text produced by a `PreProcessor`, with the mapping back to the raw input when it is known.
+pub struct Preprocessed {
+    text: String,
+    map: Option<Vec<(usize, usize)>>,
+}
+
+impl Preprocessed {
+    /// The processed text that splitters borrow from.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// A `Utf8Splitter` over the processed text, like `Utf8Splitter::new`.
+    pub fn splitter(&self, n: usize, step: usize, keep_tail: bool) -> Utf8Splitter<'_> {
+        Utf8Splitter::new(&self.text, n, step, keep_tail)
+    }
+
+    /// Maps a byte range of the processed text back to the raw input, for citations.
+    ///
+    /// A collapsed whitespace run maps back to the whole run, and characters removed between two
+    /// kept ones end up at the end of the range before them. Returns `None` after a `Custom` step or if `range`
+    /// is not on char boundaries of the processed text.
+    pub fn original_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let map = self.map.as_deref()?;
+        Some(lookup(map, range.start)?..lookup(map, range.end)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_apply_in_order() {
+        let raw = "\u{201C}Hi\u{201D},\u{7}  it\u{2019}s\n\n\tme.";
+        let pre = PreProcessor::new(vec![
+            PreStep::StripControl,
+            PreStep::ReplaceSmartQuotes,
+            PreStep::CollapseWhitespace,
+        ]);
+        assert_eq!(pre.apply(raw).text(), "\"Hi\", it's me.");
+        let upper = PreProcessor::new(vec![
+            PreStep::CollapseWhitespace,
+            PreStep::Custom(Box::new(|s| s.to_uppercase())),
+        ]);
+        let doc = upper.apply("a  b");
+        assert_eq!(doc.text(), "A B");
+        assert_eq!(doc.original_range(0..1), None);
+    }
+
+    #[test]
+    fn chunks_map_back_to_raw_input() {
+        let raw = "one   two\u{0}\u{0} thr\u{2019}e  four";
+        let pre = PreProcessor::new(vec![
+            PreStep::CollapseWhitespace,
+            PreStep::StripControl,
+            PreStep::ReplaceSmartQuotes,
+        ]);
+        let doc = Utf8Splitter::preprocessed(raw, &pre);
+        assert_eq!(doc.text(), "one two thr'e four");
+        let sp = doc.splitter(8, 0, true);
+        let cited: Vec<_> = sp
+            .split_indices()
+            .into_iter()
+            .map(|r| &raw[doc.original_range(r).unwrap()])
+            .collect();
+        assert_eq!(
+            cited,
+            vec!["one   two\u{0}\u{0} ", "thr\u{2019}e  fo", "ur"]
+        );
+        assert_eq!(sp.split(), vec!["one two ", "thr'e fo", "ur"]);
+        assert_eq!(doc.original_range(1..2), Some(1..2));
+        assert_eq!(doc.original_range(0..doc.text().len()), Some(0..raw.len()));
+    }
+}
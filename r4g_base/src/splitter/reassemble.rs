@@ -0,0 +1,170 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::splitter::chunk::Chunk;
+use crate::splitter::from_char::Utf8Splitter;
+
+/// Why a set of chunks does not reproduce its source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReassemblyError {
+    /// No chunk covers this byte range of the input.
+    Gap(Range<usize>),
+    /// The reassembled text first differs from the input at this byte offset.
+    Mismatch(usize),
+}
+
+impl ReassemblyError {
+    /// The first byte offset of the input that is not reproduced.
+    pub fn offset(&self) -> usize {
+        match self {
+            ReassemblyError::Gap(r) => r.start,
+            ReassemblyError::Mismatch(offset) => *offset,
+        }
+    }
+}
+
+impl fmt::Display for ReassemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReassemblyError::Gap(r) => write!(
+                f,
+                "bytes {}..{} are not covered by any chunk",
+                r.start, r.end
+            ),
+            ReassemblyError::Mismatch(offset) => {
+                write!(f, "reassembled text differs at byte {offset}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReassemblyError {}
+
+/// Rebuilds the text that windows of `n` characters every `step` characters were cut from, by
+/// dropping the `n - step` overlapping characters at the start of every chunk after the first.
+///
+/// - `step`: Step between window starts (`0` means `step = n`).
+///
+/// A final chunk shorter than the overlap lies entirely inside the previous one and adds nothing.
+/// This assumes `Tail::Keep`/`Tail::Drop` windows; with `step > n` the gaps cannot be recovered
+/// and the chunks are simply concatenated.
+pub fn reassemble(chunks: &[&str], step: usize, n: usize) -> String {
+    let hop = if step == 0 { n } else { step };
+    let overlap = n.saturating_sub(hop);
+    let mut out = String::with_capacity(chunks.iter().map(|c| c.len()).sum());
+    for (k, chunk) in chunks.iter().enumerate() {
+        if k == 0 || overlap == 0 {
+            out.push_str(chunk);
+            continue;
+        }
+        if let Some((skip, _)) = chunk.char_indices().nth(overlap) {
+            out.push_str(&chunk[skip..]);
+        }
+    }
+    out
+}
+
+/// Stitches chunks cut from one input back together using their byte ranges, keeping each byte
+/// once where chunks overlap.
+///
+/// Chunks may be given in any order. Uncovered parts of the input (gaps between chunks) are
+/// missing from the result; with `verify_lossless`-clean chunks the result is the input itself.
+pub fn reconstruct<M>(chunks: &[Chunk<M>]) -> String {
+    let mut order: Vec<&Chunk<M>> = chunks.iter().collect();
+    order.sort_by_key(|c| (c.range.start, c.range.end));
+    let mut out = String::new();
+    let mut covered = 0usize;
+    for c in order {
+        if c.range.end <= covered {
+            continue;
+        }
+        out.push_str(&c.text[covered.saturating_sub(c.range.start)..]);
+        covered = c.range.end;
+    }
+    out
+}
+
+/// Checks that the chunks of `splitter` cover `input` without gaps and reassemble to exactly
+/// `input`, reporting the first offset that is lost otherwise.
+///
+/// Trimming or skipping options make a splitter lossy by design and are reported as gaps or
+/// mismatches like any other loss.
+pub fn verify_lossless(input: &str, splitter: &Utf8Splitter) -> Result<(), ReassemblyError> {
+    let mut covered = 0usize;
+    for r in splitter.split_indices() {
+        if r.start > covered {
+            return Err(ReassemblyError::Gap(covered..r.start));
+        }
+        covered = covered.max(r.end);
+    }
+    if covered < input.len() {
+        return Err(ReassemblyError::Gap(covered..input.len()));
+    }
+
+    let rebuilt = reassemble(&splitter.split(), splitter.step(), splitter.n());
+    match rebuilt.bytes().zip(input.bytes()).position(|(a, b)| a != b) {
+        Some(offset) => Err(ReassemblyError::Mismatch(offset)),
+        None if rebuilt.len() != input.len() => {
+            Err(ReassemblyError::Mismatch(rebuilt.len().min(input.len())))
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::splitter::utils::{Tail, TrimPolicy};
+
+    #[test]
+    fn reassembles_overlapping_windows() {
+        let s = "a😀bcdéfg";
+        for (n, step) in [(3, 0), (3, 1), (5, 2), (4, 3), (8, 1), (20, 0)] {
+            let chunks = Utf8Splitter::new(s, n, step, true).split();
+            assert_eq!(reassemble(&chunks, step, n), s, "n={n} step={step}");
+        }
+    }
+
+    #[test]
+    fn tail_shorter_than_overlap() {
+        // windows 0..5, 1..6, 2..7, then the tail 3..7 lies inside the last full window.
+        let chunks = Utf8Splitter::new("abcdefg", 5, 1, true).split();
+        assert_eq!(chunks.last(), Some(&"defg"));
+        assert_eq!(reassemble(&chunks, 1, 5), "abcdefg");
+    }
+
+    #[test]
+    fn verify_lossless_reports_first_loss() {
+        let s = "abcdefghij";
+        assert_eq!(
+            verify_lossless(s, &Utf8Splitter::new(s, 3, 2, true)),
+            Ok(())
+        );
+        assert_eq!(
+            verify_lossless(s, &Utf8Splitter::new(s, 3, 0, false)),
+            Err(ReassemblyError::Gap(9..10))
+        );
+        assert_eq!(
+            verify_lossless(s, &Utf8Splitter::new(s, 2, 3, true)),
+            Err(ReassemblyError::Gap(2..3))
+        );
+        let spaced = "ab  cd";
+        let trimmed = Utf8Splitter::new(spaced, 3, 0, true).trim(TrimPolicy::Both);
+        assert_eq!(
+            verify_lossless(spaced, &trimmed).map_err(|e| e.offset()),
+            Err(2)
+        );
+    }
+
+    #[test]
+    fn reconstruct_from_spans() {
+        let s = "a😀bcdéfgh ij";
+        for (n, step) in [(3, 0), (3, 1), (5, 2), (4, 3), (20, 0)] {
+            let sp = Utf8Splitter::with_tail(s, n, step, Tail::PadBackward);
+            let mut chunks = sp.split_with_spans();
+            assert_eq!(reconstruct(&chunks), s, "n={n} step={step}");
+            chunks.reverse();
+            assert_eq!(reconstruct(&chunks), s, "n={n} step={step} reversed");
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::iter::Peekable;
+use std::ops::Range;
+
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::splitter::utils::to_owned_all;
+
+/// Returns the byte range of each sentence in `input`, without surrounding whitespace.
+///
+/// A sentence ends after a run of `.`, `!` or `?` (plus any closing quotes or brackets) that is
+/// followed by whitespace or the end of the input; trailing text without a terminator is the last
+/// sentence. This is a punctuation heuristic: abbreviations such as "e.g. " end a sentence too.
+pub fn sentence_spans(input: &str) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if start.is_none() {
+            if c.is_whitespace() {
+                continue;
+            }
+            start = Some(i);
+        }
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        let mut end = i + c.len_utf8();
+        while let Some((j, d)) = chars
+            .next_if(|&(_, d)| matches!(d, '.' | '!' | '?' | '"' | '\'' | ')' | ']' | '”' | '’'))
+        {
+            end = j + d.len_utf8();
+        }
+        if chars.peek().map_or(true, |&(_, d)| d.is_whitespace()) {
+            out.extend(start.take().map(|s| s..end));
+        }
+    }
+    if let Some(s) = start {
+        out.push(s..s + input[s..].trim_end().len());
+    }
+    out
+}
+
+/// Returns the byte range of each sentence in `input` at the UAX #29 sentence boundaries, as
+/// computed by `unicode-segmentation`.
+///
+/// Unlike `sentence_spans` the spans tile the input: each sentence keeps its trailing whitespace
+/// and line breaks. A period followed by a lowercase word (as in "e.g. the") does not end a
+/// sentence, but one followed by a capitalized word ("Dr. Smith") still does.
+#[cfg(feature = "unicode")]
+pub fn unicode_sentence_spans(input: &str) -> Vec<Range<usize>> {
+    input
+        .split_sentence_bound_indices()
+        .map(|(i, s)| i..i + s.len())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SentenceBackend {
+    Heuristic,
+    #[cfg(feature = "unicode")]
+    Unicode,
+}
+
+/// A splitter that emits one chunk per sentence.
+///
+/// - `input`: The input text.
+///
+/// `new` uses the punctuation heuristic of `sentence_spans`; `unicode` (feature `unicode`) uses
+/// the UAX #29 rules of `unicode_sentence_spans`. The sentences can be grouped further with
+/// `SliceSplitter`.
+pub struct SentenceSplitter<'a> {
+    input: &'a str,
+    backend: SentenceBackend,
+}
+
+impl<'a> SentenceSplitter<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            backend: SentenceBackend::Heuristic,
+        }
+    }
+
+    /// A splitter that breaks at UAX #29 sentence boundaries. Chunks keep their trailing
+    /// whitespace, so they concatenate back to `input`.
+    #[cfg(feature = "unicode")]
+    pub fn unicode(input: &'a str) -> Self {
+        Self {
+            input,
+            backend: SentenceBackend::Unicode,
+        }
+    }
+
+    /// Return the byte range of each sentence.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        match self.backend {
+            SentenceBackend::Heuristic => sentence_spans(self.input),
+            #[cfg(feature = "unicode")]
+            SentenceBackend::Unicode => unicode_sentence_spans(self.input),
+        }
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+
+    /// Segments a stream of characters into sentences as they arrive, with the punctuation
+    /// heuristic of `sentence_spans` (see `SentenceStream`).
+    pub fn from_chars<I: Iterator<Item = char>>(chars: I) -> SentenceStream<I> {
+        SentenceStream {
+            chars: chars.peekable(),
+        }
+    }
+}
+
+/// Owned sentences of a character stream, from `SentenceSplitter::from_chars`.
+///
+/// Only the current sentence is buffered. A sentence is yielded once the character after its
+/// terminator (and closing quotes or brackets) is seen to be whitespace, or the stream ends, so
+/// the output matches `sentence_spans` over the whole text.
+pub struct SentenceStream<I: Iterator<Item = char>> {
+    chars: Peekable<I>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for SentenceStream<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut sentence = String::new();
+        while let Some(c) = self.chars.next() {
+            if sentence.is_empty() && c.is_whitespace() {
+                continue;
+            }
+            sentence.push(c);
+            if !matches!(c, '.' | '!' | '?') {
+                continue;
+            }
+            while let Some(d) = self
+                .chars
+                .next_if(|&d| matches!(d, '.' | '!' | '?' | '"' | '\'' | ')' | ']' | '”' | '’'))
+            {
+                sentence.push(d);
+            }
+            if self.chars.peek().map_or(true, |d| d.is_whitespace()) {
+                return Some(sentence);
+            }
+        }
+        let len = sentence.trim_end().len();
+        sentence.truncate(len);
+        (!sentence.is_empty()).then_some(sentence)
+    }
+}
+
+/// One sentence together with the sentences around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceWindow<'a> {
+    /// Byte range of the core sentence.
+    pub core: Range<usize>,
+    /// Byte range from the first context sentence before to the last one after the core.
+    pub window: Range<usize>,
+    /// The core sentence.
+    pub core_text: &'a str,
+    /// The core sentence with its context.
+    pub window_text: &'a str,
+}
+
+/// A splitter that emits every sentence with up to `context_before` sentences before it and
+/// `context_after` sentences after it (see `sentence_spans`).
+///
+/// - `input`: The input text.
+/// - `context_before`: Number of sentences of context before the core sentence.
+/// - `context_after`: Number of sentences of context after the core sentence.
+///
+/// At the start and end of the input the context shrinks to the sentences available.
+pub struct SentenceWindowSplitter<'a> {
+    input: &'a str,
+    context_before: usize,
+    context_after: usize,
+}
+
+impl<'a> SentenceWindowSplitter<'a> {
+    pub fn new(input: &'a str, context_before: usize, context_after: usize) -> Self {
+        Self {
+            input,
+            context_before,
+            context_after,
+        }
+    }
+
+    pub fn split(&self) -> Vec<SentenceWindow<'a>> {
+        let spans = sentence_spans(self.input);
+        let last = spans.len().saturating_sub(1);
+        spans
+            .iter()
+            .enumerate()
+            .map(|(i, core)| {
+                let first = &spans[i.saturating_sub(self.context_before)];
+                let end = &spans[i.saturating_add(self.context_after).min(last)];
+                let window = first.start..end.end;
+                SentenceWindow {
+                    core: core.clone(),
+                    core_text: &self.input[core.clone()],
+                    window_text: &self.input[window.clone()],
+                    window,
+                }
+            })
+            .collect()
+    }
+
+    /// Return the owned window text of each sentence.
+    pub fn out(&self) -> Vec<String> {
+        self.split()
+            .into_iter()
+            .map(|w| w.window_text.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_end_at_terminators() {
+        let s = "  Hi there. Is 3.5 ok?! \"Yes.\" Trailing";
+        let v: Vec<_> = sentence_spans(s).into_iter().map(|r| &s[r]).collect();
+        assert_eq!(v, vec!["Hi there.", "Is 3.5 ok?!", "\"Yes.\"", "Trailing"]);
+    }
+
+    #[test]
+    fn context_shrinks_at_edges() {
+        let s = "A one. B two. C three. D four.";
+        let w = SentenceWindowSplitter::new(s, 1, 2).split();
+        assert_eq!(w.len(), 4);
+        assert_eq!(w[0].core_text, "A one.");
+        assert_eq!(w[0].window_text, "A one. B two. C three.");
+        assert_eq!(w[2].window_text, "B two. C three. D four.");
+        assert_eq!(w[3].core, 23..30);
+        assert_eq!(w[3].window, 14..30);
+    }
+
+    #[test]
+    fn zero_context_window_is_core() {
+        let s = "One. Two.";
+        for w in SentenceWindowSplitter::new(s, 0, 0).split() {
+            assert_eq!(w.core, w.window);
+        }
+    }
+
+    #[test]
+    fn splitter_uses_heuristic_by_default() {
+        let s = "One. Two!";
+        assert_eq!(SentenceSplitter::new(s).split(), vec!["One.", "Two!"]);
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn unicode_spans_tile_input() {
+        let s = "Hi there.  Is 3.5 ok?! \"Yes.\" (Sure.)\r\nNext line\u{2029}Last";
+        let v = SentenceSplitter::unicode(s).split();
+        assert_eq!(
+            v,
+            vec![
+                "Hi there.  ",
+                "Is 3.5 ok?! ",
+                "\"Yes.\" ",
+                "(Sure.)\r\n",
+                "Next line\u{2029}",
+                "Last"
+            ]
+        );
+        assert_eq!(v.concat(), s);
+        assert!(unicode_sentence_spans("").is_empty());
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn unicode_and_heuristic_differ_on_abbreviations() {
+        let s = "See e.g. the docs. Ask Dr. Smith at 5 p.m. today. The U.S. economy grew.";
+        // The heuristic ends a sentence at every period followed by a space.
+        assert_eq!(
+            SentenceSplitter::new(s).split(),
+            vec![
+                "See e.g.",
+                "the docs.",
+                "Ask Dr.",
+                "Smith at 5 p.m.",
+                "today.",
+                "The U.S.",
+                "economy grew."
+            ]
+        );
+        // UAX #29 keeps a period before a lowercase word, but still breaks before a capital.
+        assert_eq!(
+            SentenceSplitter::unicode(s)
+                .split()
+                .into_iter()
+                .map(str::trim_end)
+                .collect::<Vec<_>>(),
+            vec![
+                "See e.g. the docs.",
+                "Ask Dr.",
+                "Smith at 5 p.m. today.",
+                "The U.S. economy grew."
+            ]
+        );
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn unicode_attaches_extend_and_handles_continuations() {
+        let s = "Cafe\u{301}. Wait... what? Yes: \u{201C}no.\u{201D} ok.";
+        let v = SentenceSplitter::unicode(s).split();
+        assert_eq!(
+            v,
+            vec![
+                "Cafe\u{301}. ",
+                "Wait... what? ",
+                "Yes: \u{201C}no.\u{201D} ok."
+            ]
+        );
+    }
+
+    #[test]
+    fn from_chars_matches_in_memory_sentences() {
+        for text in [
+            "One. Two! Three?",
+            "  He said \"stop.\" Then left...  \n Trailing text  ",
+            "Version 1.2 is out. (Really!) Yes",
+            "",
+            "   ",
+            "No terminator",
+        ] {
+            let streamed: Vec<String> = SentenceSplitter::from_chars(text.chars()).collect();
+            assert_eq!(streamed, SentenceSplitter::new(text).out(), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn from_chars_is_incremental() {
+        let endless = "Hi there. ".chars().cycle();
+        let first: Vec<String> = SentenceSplitter::from_chars(endless).take(3).collect();
+        assert_eq!(first, vec!["Hi there."; 3]);
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use crate::splitter::utils::{back_chars, forward_chars, to_owned_all, SplitError};
+
+/// A splitter that prefers to break after a separator but never exceeds a hard size limit.
+///
+/// - `input`: The input string slice to split.
+/// - `preferred_sep`: The separator to break after, e.g. `". "`.
+/// - `max_chars`: Maximum number of characters per chunk.
+/// - `overlap`: Characters repeated from the end of one chunk at the start of the next.
+///
+/// Each chunk scans forward `max_chars` characters and is cut just after the last complete
+/// occurrence of `preferred_sep` in that window, so the separator stays with the text before it.
+/// When the window holds no separator the chunk is hard-cut at `max_chars`. An empty
+/// `preferred_sep` always hard-cuts.
+pub struct SmartSplitter<'a> {
+    input: &'a str,
+    preferred_sep: &'a str,
+    max_chars: usize,
+    overlap: usize,
+}
+
+impl<'a> SmartSplitter<'a> {
+    pub fn new(input: &'a str, preferred_sep: &'a str, max_chars: usize, overlap: usize) -> Self {
+        Self {
+            input,
+            preferred_sep,
+            max_chars,
+            overlap,
+        }
+    }
+
+    /// Return the byte range of each chunk.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        self.for_each_range(|r| out.push(r));
+        out
+    }
+
+    /// Return the number of chunks, running the scan without collecting them.
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        self.for_each_range(|_| count += 1);
+        count
+    }
+
+    fn for_each_range(&self, mut emit: impl FnMut(Range<usize>)) {
+        let len = self.input.len();
+        let max = self.max_chars.max(1);
+        let mut start = 0usize;
+
+        while start < len {
+            let hard_end = forward_chars(self.input, start, max, len);
+            if hard_end == len {
+                emit(start..len);
+                break;
+            }
+            let window = &self.input[start..hard_end];
+            let end = match window.rfind(self.preferred_sep) {
+                Some(i) if !self.preferred_sep.is_empty() => start + i + self.preferred_sep.len(),
+                _ => hard_end,
+            };
+            emit(start..end);
+
+            // Step back by the overlap, but always past the previous start.
+            let floor = forward_chars(self.input, start, 1, len);
+            start = back_chars(self.input, end, self.overlap, floor);
+        }
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+
+    /// Like `split`, but rejects a zero `max_chars` or an `overlap` of at least `max_chars`
+    /// instead of clamping them.
+    pub fn try_split(&self) -> Result<Vec<&'a str>, SplitError> {
+        SplitError::check_overlap(self.max_chars, self.overlap)?;
+        Ok(self.split())
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaks_after_last_separator_in_window() {
+        let s = "One. Two three. Four five six seven.";
+        let v = SmartSplitter::new(s, ". ", 20, 0).split();
+        assert_eq!(v, vec!["One. Two three. ", "Four five six seven."]);
+    }
+
+    #[test]
+    fn hard_cuts_without_separator() {
+        let s = "abcdéfghij. k";
+        let v = SmartSplitter::new(s, ". ", 4, 0).split();
+        assert_eq!(v, vec!["abcd", "éfgh", "ij. ", "k"]);
+    }
+
+    #[test]
+    fn overlap_repeats_chars_and_progresses() {
+        let s = "aa. bb. cc. dd.";
+        let v = SmartSplitter::new(s, ". ", 8, 2).split();
+        assert_eq!(v, vec!["aa. bb. ", ". cc. ", ". dd."]);
+        let tiny = SmartSplitter::new("abcdef", "", 2, 5).split();
+        assert_eq!(tiny, vec!["ab", "bc", "cd", "de", "ef"]);
+    }
+
+    #[test]
+    fn count_matches_split() {
+        let s = "aa. bb. cc. dd. eeeeeeeeee";
+        for (max, overlap) in [(3, 0), (8, 2), (30, 0), (5, 4)] {
+            let sp = SmartSplitter::new(s, ". ", max, overlap);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+
+    #[test]
+    fn try_split_rejects_overlap_at_least_max() {
+        let sp = SmartSplitter::new("a. b.", ". ", 3, 3);
+        assert_eq!(
+            sp.try_split(),
+            Err(SplitError::OverlapTooLarge {
+                overlap: 3,
+                size: 3
+            })
+        );
+        assert_eq!(
+            SmartSplitter::new("a. b.", ". ", 0, 0).try_split(),
+            Err(SplitError::ZeroWindow)
+        );
+        assert_eq!(
+            SmartSplitter::new("a. b.", ". ", 3, 1).try_split(),
+            Ok(vec!["a. ", " b."])
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::splitter::utils::SeparatorPolicy;
+
 /// Splits a string into a vector of words separated by whitespace.
 pub fn dummy(string: &str) -> Vec<String> {
     let mut result = Vec::new();
@@ -9,6 +11,81 @@ pub fn dummy(string: &str) -> Vec<String> {
     result
 }
 
+/// Splits `input` on every occurrence of `sep`, handling the separator text according to `policy`.
+///
+/// With `Drop`, empty pieces between adjacent separators are kept, as with `str::split`.
+/// With any other policy, empty pieces are omitted and the pieces concatenate back to `input`.
+/// An empty `input` yields no pieces; an empty `sep` yields `input` as a single piece.
+pub fn split_on_str<'a>(input: &'a str, sep: &str, policy: SeparatorPolicy) -> Vec<&'a str> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    if sep.is_empty() {
+        return vec![input];
+    }
+    if policy == SeparatorPolicy::Drop {
+        return input.split(sep).collect();
+    }
+
+    let mut out = Vec::new();
+    let mut start = 0usize;
+    for (i, m) in input.match_indices(sep) {
+        let end = i + m.len();
+        match policy {
+            SeparatorPolicy::AttachToPrevious => {
+                out.push(&input[start..end]);
+                start = end;
+            }
+            SeparatorPolicy::AttachToNext => {
+                if i > start {
+                    out.push(&input[start..i]);
+                }
+                start = i;
+            }
+            SeparatorPolicy::Isolate => {
+                if i > start {
+                    out.push(&input[start..i]);
+                }
+                out.push(m);
+                start = end;
+            }
+            SeparatorPolicy::Drop => unreachable!(),
+        }
+    }
+    if start < input.len() {
+        out.push(&input[start..]);
+    }
+    out
+}
+
+/// Splits `input` on every character in `delimiters`, returning borrowed tokens.
+///
+/// - `keep_delimiters`: Attach each delimiter to the token before it, so the tokens concatenate
+///   back to `input` (as with `str::split_inclusive`); otherwise delimiters are discarded.
+/// - `keep_empty`: Keep the empty tokens produced by consecutive, leading or trailing delimiters
+///   (with `keep_delimiters`, a token that is only its delimiter counts as empty). Dropping them
+///   with `keep_delimiters` drops their delimiters too.
+///
+/// An empty `input` yields no tokens; empty `delimiters` yield `input` as a single token.
+pub fn split_on_chars<'a>(
+    input: &'a str,
+    delimiters: &[char],
+    keep_delimiters: bool,
+    keep_empty: bool,
+) -> Vec<&'a str> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    let mut out: Vec<&'a str> = if keep_delimiters {
+        input.split_inclusive(delimiters).collect()
+    } else {
+        input.split(delimiters).collect()
+    };
+    if !keep_empty {
+        out.retain(|token| !token.strip_suffix(delimiters).unwrap_or(token).is_empty());
+    }
+    out
+}
 
 #[cfg(test)]
 mod tests {
@@ -27,4 +104,60 @@ mod tests {
         let result = dummy(input);
         assert_eq!(result, expected);
     }
+
+    const TEXT: &str = "one\n\ntwo\n\n\n\nthree\n\n";
+
+    #[test]
+    fn split_on_str_policies() {
+        let sep = "\n\n";
+        assert_eq!(split_on_str(TEXT, sep, SeparatorPolicy::Drop), vec!["one", "two", "", "three", ""]);
+        assert_eq!(
+            split_on_str(TEXT, sep, SeparatorPolicy::AttachToPrevious),
+            vec!["one\n\n", "two\n\n", "\n\n", "three\n\n"]
+        );
+        assert_eq!(
+            split_on_str(TEXT, sep, SeparatorPolicy::AttachToNext),
+            vec!["one", "\n\ntwo", "\n\n", "\n\nthree", "\n\n"]
+        );
+        assert_eq!(
+            split_on_str(TEXT, sep, SeparatorPolicy::Isolate),
+            vec!["one", "\n\n", "two", "\n\n", "\n\n", "three", "\n\n"]
+        );
+    }
+
+    #[test]
+    fn split_on_str_reconstructs_input() {
+        for text in [TEXT, "a. b. c", ". lead", "no separator", "a😀. b😃."] {
+            for sep in ["\n\n", ". "] {
+                for policy in [
+                    SeparatorPolicy::AttachToPrevious,
+                    SeparatorPolicy::AttachToNext,
+                    SeparatorPolicy::Isolate,
+                ] {
+                    assert_eq!(split_on_str(text, sep, policy).concat(), text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_on_chars_delimiters_and_empty_tokens() {
+        let text = ",a,b;;c|";
+        let delims = [',', ';', '|'];
+        assert_eq!(split_on_chars(text, &delims, false, false), vec!["a", "b", "c"]);
+        assert_eq!(
+            split_on_chars(text, &delims, false, true),
+            vec!["", "a", "b", "", "c", ""]
+        );
+        assert_eq!(
+            split_on_chars(text, &delims, true, false),
+            vec!["a,", "b;", "c|"]
+        );
+        let kept = split_on_chars(text, &delims, true, true);
+        assert_eq!(kept, vec![",", "a,", "b;", ";", "c|"]);
+        assert_eq!(kept.concat(), text);
+        assert_eq!(split_on_chars("héllo wörld", &[' '], true, false), vec!["héllo ", "wörld"]);
+        assert_eq!(split_on_chars("abc", &[], false, true), vec!["abc"]);
+        assert!(split_on_chars("", &delims, false, true).is_empty());
+    }
 }
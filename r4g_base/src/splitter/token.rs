@@ -0,0 +1,178 @@
+use std::ops::Range;
+
+use crate::splitter::chunk::Chunk;
+use crate::splitter::utils::to_owned_all;
+
+/// A chunk from `TokenSplitter` with the token count `count_fn` gave for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenChunk<'a> {
+    /// The chunk text, byte range and lengths.
+    pub chunk: Chunk<'a>,
+    /// Number of tokens in the chunk, as measured by `count_fn` during packing.
+    pub token_len: usize,
+}
+
+/// A splitter that packs whole words into chunks under a token budget.
+///
+/// - `input`: The input string slice to split.
+/// - `count_fn`: Counts the tokens in a span of text (e.g. a tokenizer's encode length).
+/// - `max_tokens`: Maximum number of tokens per chunk.
+///
+/// Chunks only ever break at whitespace: words are added while the whole span, as measured by
+/// `count_fn`, stays within `max_tokens`. A single word over budget is emitted on its own.
+/// Chunks run from the start of their first word to the end of their last, so whitespace between
+/// chunks is not included in any of them.
+pub struct TokenSplitter<'a, F> {
+    input: &'a str,
+    count_fn: F,
+    max_tokens: usize,
+}
+
+impl<'a, F: Fn(&str) -> usize> TokenSplitter<'a, F> {
+    pub fn word_aligned(input: &'a str, count_fn: F, max_tokens: usize) -> Self {
+        Self {
+            input,
+            count_fn,
+            max_tokens,
+        }
+    }
+
+    /// Return the byte range of each chunk.
+    pub fn split_indices(&self) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        self.for_each_range(|r, _| out.push(r));
+        out
+    }
+
+    /// Return each chunk with its character, byte and token lengths. The token length is the
+    /// count that admitted the chunk's last word, so it matches the budget check exactly.
+    pub fn chunks(&self) -> Vec<TokenChunk<'a>> {
+        let mut out = Vec::new();
+        self.for_each_range(|r, tokens| {
+            let token_len = tokens.unwrap_or_else(|| (self.count_fn)(&self.input[r.clone()]));
+            out.push(TokenChunk {
+                chunk: Chunk::new(self.input, r),
+                token_len,
+            });
+        });
+        out
+    }
+
+    /// Return the number of chunks, running the packing without collecting them.
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        self.for_each_range(|_, _| count += 1);
+        count
+    }
+
+    /// Calls `emit(range, tokens)` for each chunk, where `tokens` is the last count taken of the
+    /// whole range, or `None` for a lone word that was never measured.
+    fn for_each_range(&self, mut emit: impl FnMut(Range<usize>, Option<usize>)) {
+        let mut current: Option<(Range<usize>, Option<usize>)> = None;
+
+        for word in word_spans(self.input) {
+            current = match current.take() {
+                None => Some((word, None)),
+                Some((c, tokens)) => {
+                    let n = (self.count_fn)(&self.input[c.start..word.end]);
+                    if n <= self.max_tokens {
+                        Some((c.start..word.end, Some(n)))
+                    } else {
+                        emit(c, tokens);
+                        Some((word, None))
+                    }
+                }
+            };
+        }
+        if let Some((c, tokens)) = current {
+            emit(c, tokens);
+        }
+    }
+
+    pub fn split(&self) -> Vec<&'a str> {
+        self.split_indices()
+            .into_iter()
+            .map(|r| &self.input[r])
+            .collect()
+    }
+
+    pub fn out(&self) -> Vec<String> {
+        to_owned_all(self.split())
+    }
+}
+
+/// Byte ranges of the maximal runs of non-whitespace characters in `input`.
+pub(crate) fn word_spans(input: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut rest = input.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while rest.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        let (start, _) = rest.next()?;
+        let mut end = input.len();
+        while let Some(&(i, c)) = rest.peek() {
+            if c.is_whitespace() {
+                end = i;
+                break;
+            }
+            rest.next();
+        }
+        Some(start..end)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Roughly one token per four characters.
+    fn approx_tokens(s: &str) -> usize {
+        s.chars().count().div_ceil(4)
+    }
+
+    #[test]
+    fn packs_whole_words_under_budget() {
+        let s = "the quick brown fox jumps over the lazy dog";
+        let v = TokenSplitter::word_aligned(s, approx_tokens, 4).split();
+        assert_eq!(v, vec!["the quick brown", "fox jumps over", "the lazy dog"]);
+        assert!(v.iter().all(|c| approx_tokens(c) <= 4));
+    }
+
+    #[test]
+    fn oversized_word_is_emitted_alone() {
+        let s = "a supercalifragilistic b";
+        let v = TokenSplitter::word_aligned(s, approx_tokens, 2).split();
+        assert_eq!(v, vec!["a", "supercalifragilistic", "b"]);
+    }
+
+    #[test]
+    fn word_spans_skip_whitespace() {
+        let s = "  ab\n c😀  ";
+        let words: Vec<_> = word_spans(s).map(|r| &s[r]).collect();
+        assert_eq!(words, vec!["ab", "c😀"]);
+    }
+
+    #[test]
+    fn count_matches_split() {
+        let s = "the quick brown fox jumps over the lazy dog";
+        for max in 1..8 {
+            let sp = TokenSplitter::word_aligned(s, approx_tokens, max);
+            assert_eq!(sp.count(), sp.split().len());
+        }
+    }
+
+    #[test]
+    fn chunks_report_lengths() {
+        let s = "añb 😀😀 cdé fghijklmn";
+        let sp = TokenSplitter::word_aligned(s, approx_tokens, 2);
+        let chunks = sp.chunks();
+        let texts: Vec<_> = chunks.iter().map(|c| c.chunk.text).collect();
+        assert_eq!(texts, sp.split());
+        for c in &chunks {
+            assert_eq!(c.chunk.char_len, c.chunk.text.chars().count());
+            assert_eq!(c.chunk.byte_len, c.chunk.text.len());
+            assert_eq!(c.token_len, approx_tokens(c.chunk.text));
+        }
+        assert_eq!(chunks[0].chunk.char_len, 6);
+        assert_eq!(chunks[0].chunk.byte_len, 13);
+        assert_eq!(chunks.last().unwrap().token_len, 3);
+    }
+}
@@ -0,0 +1,239 @@
+use std::ops::Range;
+
+use crate::splitter::from_char::Utf8Splitter;
+use crate::splitter::from_list::SliceSplitter;
+use crate::splitter::sentence::SentenceSplitter;
+use crate::splitter::smart::SmartSplitter;
+use crate::splitter::token::TokenSplitter;
+
+/// The interface shared by the splitters whose chunks are contiguous ranges of their input, for
+/// code that is generic over the splitting strategy.
+///
+/// `Chunk<'s>` is what `split` yields; for the borrowing splitters it is a slice of the input
+/// (`&'a str`, `&'a [T]`), which outlives the borrow of the splitter itself.
+pub trait Splitter {
+    type Chunk<'s>
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<Self::Chunk<'_>>;
+
+    /// The number of chunks `split` returns.
+    fn chunk_count(&self) -> usize;
+
+    /// The range of each chunk in the input: bytes for text, elements for slices.
+    fn split_indices(&self) -> Vec<Range<usize>>;
+}
+
+/// The object-safe part of `Splitter` for text splitters, with owned chunks, so a strategy picked
+/// at runtime can be held as a `Box<dyn DynSplitter>`.
+pub trait DynSplitter {
+    /// Return the owned text of each chunk.
+    fn out(&self) -> Vec<String>;
+
+    /// The number of chunks `out` returns.
+    fn chunk_count(&self) -> usize;
+
+    /// The byte range of each chunk in the input.
+    fn split_indices(&self) -> Vec<Range<usize>>;
+}
+
+impl<'a> Splitter for Utf8Splitter<'a> {
+    type Chunk<'s>
+        = &'a str
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<&'a str> {
+        self.split()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.chunk_count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a, T> Splitter for SliceSplitter<'a, T> {
+    type Chunk<'s>
+        = &'a [T]
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<&'a [T]> {
+        self.split()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.chunk_count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a> Splitter for SmartSplitter<'a> {
+    type Chunk<'s>
+        = &'a str
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<&'a str> {
+        self.split()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a, F: Fn(&str) -> usize> Splitter for TokenSplitter<'a, F> {
+    type Chunk<'s>
+        = &'a str
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<&'a str> {
+        self.split()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a> Splitter for SentenceSplitter<'a> {
+    type Chunk<'s>
+        = &'a str
+    where
+        Self: 's;
+
+    fn split(&self) -> Vec<&'a str> {
+        self.split()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.split_indices().len()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a> DynSplitter for Utf8Splitter<'a> {
+    fn out(&self) -> Vec<String> {
+        self.out()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.chunk_count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a> DynSplitter for SmartSplitter<'a> {
+    fn out(&self) -> Vec<String> {
+        self.out()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a, F: Fn(&str) -> usize> DynSplitter for TokenSplitter<'a, F> {
+    fn out(&self) -> Vec<String> {
+        self.out()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.count()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+impl<'a> DynSplitter for SentenceSplitter<'a> {
+    fn out(&self) -> Vec<String> {
+        self.out()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.split_indices().len()
+    }
+
+    fn split_indices(&self) -> Vec<Range<usize>> {
+        self.split_indices()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_len<S: Splitter>(s: &S) -> usize {
+        s.split_indices().iter().map(|r| r.len()).sum()
+    }
+
+    fn from_config<'a>(strategy: &str, input: &'a str) -> Box<dyn DynSplitter + 'a> {
+        match strategy {
+            "chars" => Box::new(Utf8Splitter::new(input, 4, 0, true)),
+            "words" => Box::new(TokenSplitter::word_aligned(
+                input,
+                |s: &str| s.split_whitespace().count(),
+                2,
+            )),
+            "smart" => Box::new(SmartSplitter::new(input, ". ", 8, 0)),
+            _ => Box::new(SentenceSplitter::new(input)),
+        }
+    }
+
+    #[test]
+    fn generic_over_splitters() {
+        let s = "ab. cd ef. gh";
+        assert_eq!(total_len(&Utf8Splitter::new(s, 4, 0, true)), s.len());
+        assert_eq!(total_len(&SliceSplitter::new(&[1, 2, 3], 2, 1, false)), 4);
+        let sp = SliceSplitter::new(&[1, 2, 3], 2, 0, true);
+        assert_eq!(Splitter::split(&sp), vec![&[1, 2][..], &[3][..]]);
+        assert_eq!(Splitter::chunk_count(&sp), 2);
+    }
+
+    #[test]
+    fn dyn_splitter_from_config() {
+        let s = "ab. cd ef. gh";
+        for (strategy, expected) in [
+            ("chars", vec!["ab. ", "cd e", "f. g", "h"]),
+            ("words", vec!["ab. cd", "ef. gh"]),
+            ("smart", vec!["ab. ", "cd ef. ", "gh"]),
+            ("sentences", vec!["ab.", "cd ef.", "gh"]),
+        ] {
+            let sp = from_config(strategy, s);
+            assert_eq!(sp.out(), expected, "{strategy}");
+            assert_eq!(sp.chunk_count(), expected.len(), "{strategy}");
+            let texts: Vec<_> = sp.split_indices().into_iter().map(|r| &s[r]).collect();
+            assert_eq!(texts, expected, "{strategy}");
+        }
+    }
+}
@@ -1,41 +1,618 @@
+use std::fmt;
 use std::ops::Range;
 
+/// What to do with the trailing elements that don't fill a whole window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tail {
+    /// Discard the trailing elements.
+    #[default]
+    Drop,
+    /// Emit the trailing elements as a final, shorter window.
+    Keep,
+    /// Emit a final window of length `n` ending at `len`, extending its start backward
+    /// (possibly overlapping the previous window). If `len < n` the single window is `0..len`.
+    PadBackward,
+    /// Only emit full windows of length `n`. Behaves like `Drop`, but states the fixed-shape intent.
+    Exact,
+}
+
+impl From<bool> for Tail {
+    fn from(keep_tail: bool) -> Self {
+        if keep_tail {
+            Tail::Keep
+        } else {
+            Tail::Drop
+        }
+    }
+}
+
+/// A tail policy that can also depend on the size of the remainder, for the splitters built on
+/// `sliding_windows` (such as `SliceSplitter`). Every `Tail` (and `bool`) converts into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TailPolicy {
+    /// As `Tail::Drop`.
+    #[default]
+    Drop,
+    /// As `Tail::Keep`.
+    Keep,
+    /// As `Tail::PadBackward`.
+    PadBackward,
+    /// As `Tail::Exact`.
+    Exact,
+    /// Merge a tail (the window `Keep` would emit) of fewer than this many elements into the last
+    /// full window, which then runs to the end of the input; keep a longer tail as its own window.
+    /// An input shorter than `n` becomes a single window of the whole input.
+    MergeBelow(usize),
+    /// Keep a tail of at least this many elements, drop a shorter one, e.g. `n / 10` to drop
+    /// tails under 10% of the window size.
+    DropBelow(usize),
+}
+
+impl From<Tail> for TailPolicy {
+    fn from(tail: Tail) -> Self {
+        match tail {
+            Tail::Drop => TailPolicy::Drop,
+            Tail::Keep => TailPolicy::Keep,
+            Tail::PadBackward => TailPolicy::PadBackward,
+            Tail::Exact => TailPolicy::Exact,
+        }
+    }
+}
+
+impl From<bool> for TailPolicy {
+    fn from(keep_tail: bool) -> Self {
+        Tail::from(keep_tail).into()
+    }
+}
+
+/// The shape of the windows of `sliding_windows`: `full` windows `i * hop..i * hop + n`, then
+/// what the tail policy does with the remaining `len - full * hop` elements.
+struct WindowLayout {
+    hop: usize,
+    n: usize,
+    full: usize,
+    /// The last window, when it is not a plain full window.
+    last: Option<Range<usize>>,
+    /// Whether `last` replaces the last full window (a merge) rather than following it.
+    merged: bool,
+}
+
+impl WindowLayout {
+    /// `None` when `n` and `step` are both `0` and there are no windows.
+    fn new(len: usize, n: usize, step: usize, tail: TailPolicy) -> Option<Self> {
+        let hop = if step == 0 { n } else { step };
+        if hop == 0 {
+            return None;
+        }
+        let full = if len >= n { (len - n) / hop + 1 } else { 0 };
+        let tail_start = full.saturating_mul(hop);
+        let rest = len.saturating_sub(tail_start);
+        let (last, merged) = match tail {
+            _ if rest == 0 => (None, false),
+            TailPolicy::Keep => (Some(tail_start..len), false),
+            // Only pad when the last full window stops short of the end; with an overlapping
+            // step it often reaches it already.
+            TailPolicy::PadBackward if full > 0 && (full - 1) * hop + n >= len => (None, false),
+            TailPolicy::PadBackward => (Some(len.saturating_sub(n)..len), false),
+            TailPolicy::Drop | TailPolicy::Exact => (None, false),
+            TailPolicy::MergeBelow(_) if full == 0 => (Some(0..len), false),
+            TailPolicy::MergeBelow(min) if rest < min => (Some((full - 1) * hop..len), true),
+            TailPolicy::MergeBelow(_) => (Some(tail_start..len), false),
+            TailPolicy::DropBelow(min) if rest < min => (None, false),
+            TailPolicy::DropBelow(_) => (Some(tail_start..len), false),
+        };
+        Some(Self {
+            hop,
+            n,
+            full,
+            last,
+            merged,
+        })
+    }
+
+    fn count(&self) -> usize {
+        self.full + usize::from(self.last.is_some() && !self.merged)
+    }
+
+    fn get(&self, index: usize) -> Option<Range<usize>> {
+        let count = self.count();
+        if index >= count {
+            return None;
+        }
+        match &self.last {
+            Some(last) if index + 1 == count => Some(last.clone()),
+            _ => Some(index * self.hop..index * self.hop + self.n),
+        }
+    }
+}
+
+/// Why splitting parameters were rejected, e.g. by a `try_split` method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitError {
+    /// The window size is zero, which would only produce empty chunks.
+    ZeroWindow,
+    /// Both the window size and the step are zero, so the windows would never advance.
+    ZeroWindowAndStep,
+    /// The overlap is at least the chunk size, so consecutive chunks could not advance by it.
+    OverlapTooLarge { overlap: usize, size: usize },
+    /// The split position at this index is smaller than the one before it.
+    UnsortedPositions { index: usize },
+    /// A split position is past the end of the input, which has `len` characters.
+    PositionOutOfRange { position: usize, len: usize },
+    /// The input was to be divided into zero parts.
+    ZeroParts,
+    /// Inputs that must be split in step have different lengths.
+    LengthMismatch { left: usize, right: usize },
+    /// The window at this index overlaps the one before it, so items cannot be moved into both.
+    OverlappingWindows { index: usize },
+    /// A row-major grid of `len` elements does not divide into rows of `width`.
+    RaggedGrid { len: usize, width: usize },
+}
+
+impl SplitError {
+    /// Checks a window size `n` and `step` as taken by the fixed-size splitters.
+    pub(crate) fn check_window(n: usize, step: usize) -> Result<(), SplitError> {
+        match (n, step) {
+            (0, 0) => Err(SplitError::ZeroWindowAndStep),
+            (0, _) => Err(SplitError::ZeroWindow),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a maximum chunk `size` and `overlap` as taken by the overlap-based splitters.
+    pub(crate) fn check_overlap(size: usize, overlap: usize) -> Result<(), SplitError> {
+        if size == 0 {
+            return Err(SplitError::ZeroWindow);
+        }
+        if overlap >= size {
+            return Err(SplitError::OverlapTooLarge { overlap, size });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::ZeroWindow => write!(f, "window size must be greater than zero"),
+            SplitError::ZeroWindowAndStep => {
+                write!(f, "window size and step are both zero")
+            }
+            SplitError::OverlapTooLarge { overlap, size } => {
+                write!(
+                    f,
+                    "overlap {overlap} must be smaller than the chunk size {size}"
+                )
+            }
+            SplitError::UnsortedPositions { index } => {
+                write!(
+                    f,
+                    "split position {index} is smaller than the one before it"
+                )
+            }
+            SplitError::PositionOutOfRange { position, len } => {
+                write!(
+                    f,
+                    "split position {position} is past the end ({len} characters)"
+                )
+            }
+            SplitError::ZeroParts => write!(f, "number of parts must be greater than zero"),
+            SplitError::LengthMismatch { left, right } => {
+                write!(f, "inputs have different lengths ({left} and {right})")
+            }
+            SplitError::OverlappingWindows { index } => {
+                write!(f, "window {index} overlaps the window before it")
+            }
+            SplitError::RaggedGrid { len, width } => {
+                write!(
+                    f,
+                    "grid of {len} elements is not a multiple of width {width}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// A splitting configuration that is valid but often not what was meant, as reported by
+/// `Utf8Splitter::checked_new`. Unlike `SplitError`, splitting goes ahead regardless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitWarning {
+    /// A `step` of `0` is read as `step = n`, i.e., no overlap (not full overlap).
+    ZeroStepMeansNoOverlap,
+    /// `step` is larger than `n`, so `gap` elements between consecutive windows are skipped.
+    StepSkipsElements { gap: usize },
+    /// A window size of `0` yields only empty chunks.
+    ZeroWindow,
+    /// The tail is dropped, so the last `len` elements of the input are in no chunk.
+    TailDropped { len: usize },
+}
+
+impl SplitWarning {
+    /// Lists the warnings for windows of `n` over `len` elements every `step`, in the order of
+    /// the variants.
+    pub(crate) fn check(len: usize, n: usize, step: usize, tail: Tail) -> Vec<SplitWarning> {
+        let mut warnings = Vec::new();
+        if step == 0 && n > 0 {
+            warnings.push(SplitWarning::ZeroStepMeansNoOverlap);
+        }
+        if n > 0 && step > n {
+            warnings.push(SplitWarning::StepSkipsElements { gap: step - n });
+        }
+        if n == 0 {
+            warnings.push(SplitWarning::ZeroWindow);
+            return warnings;
+        }
+        let covered = match window_count(len, n, step, tail) {
+            0 => 0,
+            count => nth_window(len, n, step, tail, count - 1).map_or(0, |r| r.end),
+        };
+        if covered < len {
+            warnings.push(SplitWarning::TailDropped { len: len - covered });
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for SplitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitWarning::ZeroStepMeansNoOverlap => {
+                write!(f, "step 0 is interpreted as step = n, i.e., no overlap")
+            }
+            SplitWarning::StepSkipsElements { gap } => {
+                write!(
+                    f,
+                    "step is larger than n, skipping {gap} elements between windows"
+                )
+            }
+            SplitWarning::ZeroWindow => write!(f, "window size 0 yields only empty chunks"),
+            SplitWarning::TailDropped { len } => {
+                write!(f, "the last {len} elements are dropped with the tail")
+            }
+        }
+    }
+}
+
+/// Which ends of a chunk to strip of whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrimPolicy {
+    #[default]
+    None,
+    Start,
+    End,
+    Both,
+}
+
+impl TrimPolicy {
+    /// Shrinks the byte range `r` of `input` so it excludes leading and/or trailing whitespace.
+    /// A range that is all whitespace collapses to an empty range.
+    pub fn apply(self, input: &str, r: Range<usize>) -> Range<usize> {
+        let s = &input[r.clone()];
+        let start = match self {
+            TrimPolicy::Start | TrimPolicy::Both => r.end - s.trim_start().len(),
+            TrimPolicy::None | TrimPolicy::End => r.start,
+        };
+        let end = match self {
+            TrimPolicy::End | TrimPolicy::Both => r.start + s.trim_end().len(),
+            TrimPolicy::None | TrimPolicy::Start => r.end,
+        };
+        start..end.max(start)
+    }
+}
+
+/// What to do with the delimiter text when splitting on a separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeparatorPolicy {
+    /// Discard separators (like `str::split`).
+    #[default]
+    Drop,
+    /// Keep each separator at the end of the piece before it.
+    AttachToPrevious,
+    /// Keep each separator at the start of the piece after it.
+    AttachToNext,
+    /// Emit each separator as a piece of its own.
+    Isolate,
+}
+
 /// Returns an iterator over fixed-size, possibly overlapping ranges within a slice of length `len`.
 ///
 /// - `n`: Size of each range.
 /// - `step`: Step between the starts of ranges (`0` means `step = n`, i.e., non-overlapping).
-/// - `keep_tail`: If true, includes a final range for any remaining elements at the end.
+/// - `tail`: How to handle remaining elements at the end (a `Tail`, a `TailPolicy`, or a `bool`
+///   for `Tail::Keep`/`Tail::Drop`).
 ///
 /// Each item produced is a `Range<usize>` representing the bounds of the subrange.
+///
+/// With `n == 0` every range is empty: one at each multiple of `step` up to and including `len`.
+/// With `n` and `step` both `0` the windows could never advance, so none are produced.
 #[inline]
 pub fn sliding_windows(
     len: usize,
     n: usize,
     step: usize, // 0 ⇒ hop = n
-    keep_tail: bool,
+    tail: impl Into<TailPolicy>,
 ) -> impl Iterator<Item = Range<usize>> {
+    let layout = WindowLayout::new(len, n, step, tail.into());
+    let count = layout.as_ref().map_or(0, WindowLayout::count);
+    (0..count).filter_map(move |i| layout.as_ref()?.get(i))
+}
+
+/// Returns `count` ranges of size `n` whose starts are spread evenly over `0..=len - n`, for
+/// sampling a long input at a fixed number of positions whatever its length.
+///
+/// - `len`: Length of the input.
+/// - `n`: Size of each range; an `n` of at least `len` gives the single range `0..len`.
+/// - `count`: Number of ranges. `1` gives the range at the start; more than the `len - n + 1`
+///   distinct positions is clamped to one range per position.
+///
+/// The first range starts at `0` and the last ends at `len`; starts in between are rounded to the
+/// nearest position. Unlike `sliding_windows` this does not tile the input.
+pub fn sliding_windows_sampled(len: usize, n: usize, count: usize) -> Vec<Range<usize>> {
+    if n == 0 || count == 0 {
+        return Vec::new();
+    }
+    let n = n.min(len);
+    let span = len - n;
+    let count = count.min(span + 1);
+    let intervals = (count - 1) as u128;
+    let mut out: Vec<Range<usize>> = Vec::with_capacity(count);
+    for i in 0..count as u128 {
+        let start = match intervals {
+            0 => 0,
+            _ => ((i * span as u128 + intervals / 2) / intervals) as usize,
+        };
+        if out.last().map_or(true, |r| r.start != start) {
+            out.push(start..start + n);
+        }
+    }
+    out
+}
 
-    let hop = if step == 0 { n } else { step };
-    let mut i = 0usize;
-    let mut emitted = false;
+/// Divides `0..len` into `min(k, len)` contiguous ranges whose lengths differ by at most one, the
+/// longer ones first. No range is empty; `k = 0` or `len = 0` gives none.
+pub fn split_into_k(len: usize, k: usize) -> Vec<Range<usize>> {
+    let k = k.min(len);
+    if k == 0 {
+        return Vec::new();
+    }
+    let (base, extra) = (len / k, len % k);
+    let mut out = Vec::with_capacity(k);
+    let mut start = 0usize;
+    for i in 0..k {
+        let end = start + base + usize::from(i < extra);
+        out.push(start..end);
+        start = end;
+    }
+    out
+}
 
-    std::iter::from_fn(move || {
-        if i + n <= len {
-            let r = i..i + n;
-            i = i.saturating_add(hop);
-            return Some(r);
+/// Returns the `index`-th range that `sliding_windows(len, n, step, tail)` would produce,
+/// computed arithmetically without producing the others.
+pub fn nth_window(
+    len: usize,
+    n: usize,
+    step: usize,
+    tail: impl Into<TailPolicy>,
+    index: usize,
+) -> Option<Range<usize>> {
+    WindowLayout::new(len, n, step, tail.into())?.get(index)
+}
+
+/// Returns how many ranges `sliding_windows(len, n, step, tail)` would produce, without producing
+/// them. Zero when `n` and `step` are both `0`.
+pub fn window_count(len: usize, n: usize, step: usize, tail: impl Into<TailPolicy>) -> usize {
+    WindowLayout::new(len, n, step, tail.into()).map_or(0, |layout| layout.count())
+}
+
+/// Returns the ranges of `0..len` not covered by any window of `sliding_windows(len, n, step, tail)`.
+///
+/// Gaps appear when `step > n` or when a tail is dropped; an empty result means every element
+/// ends up in at least one window. Useful to assert full coverage or to log what is discarded.
+pub fn coverage(
+    len: usize,
+    n: usize,
+    step: usize,
+    tail: impl Into<TailPolicy>,
+) -> Vec<Range<usize>> {
+    let mut gaps = Vec::new();
+    let mut covered = 0usize;
+    for r in sliding_windows(len, n, step, tail) {
+        if r.start > covered {
+            gaps.push(covered..r.start);
         }
-        if keep_tail && !emitted && i < len {
-            emitted = true;
-            return Some(i..len);
+        covered = covered.max(r.end);
+    }
+    if covered < len {
+        gaps.push(covered..len);
+    }
+    gaps
+}
+
+/// 64-bit FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// 64-bit FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A stable 64-bit hash of `bytes`: FNV-1a with the standard offset basis `0xcbf29ce484222325`
+/// and prime `0x100000001b3`.
+///
+/// Unlike `std`'s `DefaultHasher`, the result is fixed by this definition and never changes across
+/// runs, platforms or Rust versions, so it can be persisted (e.g. as an embedding cache key).
+/// It is not collision-resistant against adversarial input.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut h = FNV_OFFSET;
+    for &b in bytes {
+        h ^= u64::from(b);
+        h = h.wrapping_mul(FNV_PRIME);
+    }
+    h
+}
+
+/// The stable hash of a chunk's text, `stable_hash` over its UTF-8 bytes. This is the hash used by
+/// `Utf8Splitter::split_hashed` and `Chunk::content_hash`, so external code can compute matching
+/// keys.
+pub fn hash_chunk(chunk: &str) -> u64 {
+    stable_hash(chunk.as_bytes())
+}
+
+/// A `Hasher` computing the same FNV-1a as `stable_hash` over everything written to it.
+///
+/// The result is only as stable as the `Hash` impl feeding it: integers are written in native
+/// byte order and `usize` at native width, so hashes of such values differ across platforms.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl std::hash::Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
         }
-        None
-    })
+    }
+}
+
+/// Converts borrowed windows into owned values: `&str` into `String`, `&[T]` into `Vec<T>`,
+/// or any other `&T` into `T::Owned`.
+pub fn to_owned_all<T: ToOwned + ?Sized>(windows: Vec<&T>) -> Vec<T::Owned> {
+    windows.to_owned_chunks()
+}
+
+/// Converts any iterator of borrowed chunks into owned ones without collecting the borrowed
+/// chunks first: `&str` into `String`, `&[T]` into `Vec<T>` (for `T: Clone`), or any other `&B`
+/// into `B::Owned`.
+pub trait ToOwnedChunks {
+    type Owned;
+
+    /// Collect owned copies of the chunks, reserving the iterator's lower size bound up front.
+    fn to_owned_chunks(self) -> Vec<Self::Owned>;
+}
+
+impl<'a, I, B> ToOwnedChunks for I
+where
+    I: IntoIterator<Item = &'a B>,
+    B: ToOwned + ?Sized + 'a,
+{
+    type Owned = B::Owned;
+
+    fn to_owned_chunks(self) -> Vec<B::Owned> {
+        let iter = self.into_iter();
+        let mut out = Vec::with_capacity(iter.size_hint().0);
+        out.extend(iter.map(ToOwned::to_owned));
+        out
+    }
+}
+
+/// Maps `f` over `items` in parallel with the `rayon` feature, keeping the order of `items`;
+/// without it, maps them one by one on the calling thread. Runs of under 64 items are not split
+/// across threads.
+pub(crate) fn par_map_ordered<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    par_map_runs(items, 64, f)
+}
+
+/// Like `par_map_ordered`, with at least `min_run` items per rayon task; use a small `min_run`
+/// for items that are expensive to map on their own, such as whole documents.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_map_runs<T, U, F>(items: &[T], min_run: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    use rayon::prelude::*;
+    items.par_iter().with_min_len(min_run.max(1)).map(f).collect()
+}
+
+/// Sequential `par_map_runs`, used without the `rayon` feature.
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn par_map_runs<T, U, F>(items: &[T], _min_run: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    items.iter().map(f).collect()
+}
+
+/// Whether `c` is in one of the combining diacritical mark blocks.
+pub(crate) fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+/// Whether a boundary just before `c` would split it from the character it belongs to: combining
+/// marks, ZWJ, variation selectors, emoji skin-tone modifiers and tag characters (flag sequences).
+/// A hand-rolled subset of the grapheme `Extend` rules, not full UAX #29.
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn is_cluster_extend(c: char) -> bool {
+    is_combining_mark(c)
+        || matches!(
+            c,
+            '\u{200D}'
+                | '\u{FE00}'..='\u{FE0F}'
+                | '\u{1F3FB}'..='\u{1F3FF}'
+                | '\u{E0020}'..='\u{E007F}'
+                | '\u{E0100}'..='\u{E01EF}'
+        )
+}
+
+/// Byte offset `k` characters after `pos`, clamped to `end`.
+pub(crate) fn forward_chars(input: &str, pos: usize, k: usize, end: usize) -> usize {
+    input[pos..end]
+        .char_indices()
+        .nth(k)
+        .map_or(end, |(i, _)| pos + i)
+}
+
+/// Byte offset `k` characters before `pos`, clamped to `floor`.
+pub(crate) fn back_chars(input: &str, pos: usize, k: usize, floor: usize) -> usize {
+    if k == 0 {
+        return pos;
+    }
+    input[floor..pos]
+        .char_indices()
+        .rev()
+        .nth(k - 1)
+        .map_or(floor, |(i, _)| floor + i)
+}
+
+/// A small LCG standing in for a random source in tests: `next(m)` is roughly uniform in `0..m`.
+#[cfg(test)]
+pub(crate) fn lcg(mut seed: u64) -> impl FnMut(u64) -> usize {
+    move |m| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 33) % m) as usize
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn basic_non_overlapping() {
@@ -77,5 +654,309 @@ mod tests {
     fn zero_width_ranges() {
         let ranges: Vec<_> = sliding_windows(3, 0, 1, false).collect();
         assert_eq!(ranges, vec![0..0, 1..1, 2..2, 3..3]);
+        let ranges: Vec<_> = sliding_windows(5, 0, 2, Tail::Keep).collect();
+        assert_eq!(ranges, vec![0..0, 2..2, 4..4]);
+        assert_eq!(sliding_windows(3, 0, 0, true).count(), 0);
+    }
+
+    #[test]
+    fn pad_backward_tail() {
+        let ranges: Vec<_> = sliding_windows(10, 4, 0, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..4, 4..8, 6..10]);
+    }
+
+    #[test]
+    fn pad_backward_with_overlapping_step() {
+        let ranges: Vec<_> = sliding_windows(7, 4, 1, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..4, 1..5, 2..6, 3..7]);
+        let ranges: Vec<_> = sliding_windows(6, 4, 2, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..4, 2..6]);
+        let ranges: Vec<_> = sliding_windows(7, 4, 2, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..4, 2..6, 3..7]);
+        let ranges: Vec<_> = sliding_windows(10, 2, 3, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..2, 3..5, 6..8, 8..10]);
+    }
+
+    #[test]
+    fn pad_backward_no_tail_needed() {
+        let ranges: Vec<_> = sliding_windows(9, 3, 0, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..3, 3..6, 6..9]);
+    }
+
+    #[test]
+    fn pad_backward_n_greater_than_len() {
+        let ranges: Vec<_> = sliding_windows(3, 5, 0, Tail::PadBackward).collect();
+        assert_eq!(ranges, vec![0..3]);
+    }
+
+    #[test]
+    fn exact_only_full_windows() {
+        let ranges: Vec<_> = sliding_windows(10, 4, 0, Tail::Exact).collect();
+        assert_eq!(ranges, vec![0..4, 4..8]);
+    }
+
+    #[test]
+    fn to_owned_all_strs_and_slices() {
+        assert_eq!(
+            to_owned_all(vec!["ab", "c"]),
+            vec!["ab".to_string(), "c".to_string()]
+        );
+        assert_eq!(
+            to_owned_all(vec![&[1, 2][..], &[3][..]]),
+            vec![vec![1, 2], vec![3]]
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn coverage_reports_gaps() {
+        assert!(coverage(10, 3, 0, true).is_empty());
+        assert!(coverage(8, 5, 2, Tail::PadBackward).is_empty());
+        assert_eq!(coverage(10, 3, 0, false), vec![9..10]);
+        assert_eq!(coverage(10, 2, 4, true), vec![2..4, 6..8]);
+        assert_eq!(coverage(5, 10, 0, Tail::Exact), vec![0..5]);
+    }
+
+    #[test]
+    fn stable_hash_known_values() {
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn split_error_checks() {
+        assert_eq!(
+            SplitError::check_window(0, 0),
+            Err(SplitError::ZeroWindowAndStep)
+        );
+        assert_eq!(SplitError::check_window(0, 2), Err(SplitError::ZeroWindow));
+        assert_eq!(SplitError::check_window(3, 0), Ok(()));
+        assert_eq!(
+            SplitError::check_overlap(4, 4),
+            Err(SplitError::OverlapTooLarge {
+                overlap: 4,
+                size: 4
+            })
+        );
+        assert_eq!(SplitError::check_overlap(4, 3), Ok(()));
+    }
+
+    #[test]
+    fn to_owned_chunks_from_iterators() {
+        let s = "abcdef";
+        let owned = sliding_windows(s.len(), 2, 0, true)
+            .map(|r| &s[r])
+            .to_owned_chunks();
+        assert_eq!(owned, vec!["ab", "cd", "ef"]);
+        let data = [1, 2, 3];
+        assert_eq!(data.chunks(2).to_owned_chunks(), vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn stable_hasher_matches_stable_hash() {
+        use std::hash::Hasher;
+        let mut h = StableHasher::default();
+        h.write(b"foo");
+        h.write(b"bar");
+        assert_eq!(h.finish(), stable_hash(b"foobar"));
+        assert_eq!(hash_chunk("foobar"), stable_hash(b"foobar"));
+    }
+
+    #[test]
+    fn nth_window_matches_sliding_windows() {
+        for tail in [Tail::Drop, Tail::Keep, Tail::PadBackward, Tail::Exact] {
+            for (len, n, step) in [
+                (10, 3, 0),
+                (8, 5, 2),
+                (5, 10, 0),
+                (0, 3, 0),
+                (10, 2, 4),
+                (7, 5, 1),
+            ] {
+                let all: Vec<_> = sliding_windows(len, n, step, tail).collect();
+                for (k, r) in all.iter().enumerate() {
+                    assert_eq!(nth_window(len, n, step, tail, k).as_ref(), Some(r));
+                }
+                assert_eq!(nth_window(len, n, step, tail, all.len()), None);
+            }
+        }
+    }
+
+    fn any_tail() -> impl Strategy<Value = Tail> {
+        prop::sample::select(vec![Tail::Drop, Tail::Keep, Tail::PadBackward, Tail::Exact])
+    }
+
+    proptest! {
+        #[test]
+        fn window_count_matches_sliding_windows(
+            len in 0..40usize,
+            n in 0..10usize,
+            step in 0..12usize,
+            tail in any_tail(),
+        ) {
+            prop_assert_eq!(
+                window_count(len, n, step, tail),
+                sliding_windows(len, n, step, tail).count()
+            );
+        }
+
+        #[test]
+        fn sliding_windows_invariants(
+            len in 0..64usize,
+            n in 0..16usize,
+            step in 0..20usize,
+            keep_tail: bool,
+        ) {
+            prop_assume!(n > 0 || step > 0);
+            let ranges: Vec<_> = sliding_windows(len, n, step, keep_tail).collect();
+            prop_assert!(ranges.iter().all(|r| r.start <= r.end && r.end <= len), "{:?}", ranges);
+            prop_assert!(ranges.windows(2).all(|w| w[0].start <= w[1].start), "{:?}", ranges);
+            if keep_tail && n > 0 && step <= n {
+                prop_assert!(coverage(len, n, step, true).is_empty(), "{:?}", ranges);
+            }
+        }
+
+        #[test]
+        fn padded_windows_are_full_and_reach_the_end(
+            len in 0..64usize,
+            n in 1..16usize,
+            step in 0..20usize,
+        ) {
+            let padded: Vec<_> = sliding_windows(len, n, step, Tail::PadBackward).collect();
+            prop_assert!(padded.windows(2).all(|w| w[0].start < w[1].start), "{:?}", padded);
+            prop_assert!(padded.iter().all(|r| r.len() == n.min(len)), "{:?}", padded);
+            prop_assert!(
+                step > n || padded.last().map_or(len == 0, |r| r.end == len),
+                "{:?}",
+                padded
+            );
+        }
+
+        #[test]
+        fn sampled_windows_are_spread_over_the_input(
+            len in 1..500usize,
+            n in 1..50usize,
+            count in 1..40usize,
+        ) {
+            let v = sliding_windows_sampled(len, n, count);
+            prop_assert!(v.windows(2).all(|w| w[0].start < w[1].start));
+            prop_assert!(v.iter().all(|r| r.end <= len && r.len() == n.min(len)));
+            prop_assert_eq!(v.len(), count.min(len.saturating_sub(n) + 1));
+            prop_assert!(v.len() == 1 || v.last().unwrap().end == len);
+        }
+    }
+
+    #[test]
+    fn sampled_windows_spread_evenly() {
+        let starts = |len, n, count| -> Vec<usize> {
+            sliding_windows_sampled(len, n, count)
+                .into_iter()
+                .map(|r| r.start)
+                .collect()
+        };
+        assert_eq!(starts(100, 10, 4), vec![0, 30, 60, 90]);
+        assert_eq!(starts(100, 10, 3), vec![0, 45, 90]);
+        assert_eq!(starts(100, 10, 1), vec![0]);
+        assert_eq!(starts(13, 10, 9), vec![0, 1, 2, 3]);
+        assert_eq!(sliding_windows_sampled(5, 8, 3), vec![0..5]);
+        assert!(sliding_windows_sampled(100, 0, 3).is_empty());
+        assert!(sliding_windows_sampled(100, 10, 0).is_empty());
+    }
+
+    #[test]
+    fn par_map_ordered_keeps_order() {
+        let items: Vec<u64> = (0..10_000).collect();
+        let doubled = par_map_ordered(&items, |&x| x * 2);
+        assert!(doubled.iter().enumerate().all(|(i, &x)| x == 2 * i as u64));
+        assert_eq!(par_map_ordered(&items[..3], |&x| x + 1), vec![1, 2, 3]);
+        assert!(par_map_ordered(&items[..0], |&x| x).is_empty());
+    }
+
+    #[test]
+    fn split_into_k_balances_lengths() {
+        let parts = split_into_k(10_007, 8);
+        assert_eq!(parts.len(), 8);
+        assert_eq!((parts[0].len(), parts[7].len()), (1251, 1250));
+        assert_eq!((parts[0].start, parts[7].end), (0, 10_007));
+        assert!(parts.windows(2).all(|w| w[0].end == w[1].start));
+        assert_eq!(split_into_k(3, 5), vec![0..1, 1..2, 2..3]);
+        assert!(split_into_k(0, 4).is_empty());
+        assert!(split_into_k(4, 0).is_empty());
+    }
+
+    #[test]
+    fn tail_policies_depending_on_remainder() {
+        let windows = |len, n, step, tail: TailPolicy| -> Vec<Range<usize>> {
+            let all: Vec<_> = sliding_windows(len, n, step, tail).collect();
+            assert_eq!(all.len(), window_count(len, n, step, tail));
+            for (i, r) in all.iter().enumerate() {
+                assert_eq!(nth_window(len, n, step, tail, i).as_ref(), Some(r));
+            }
+            all
+        };
+        assert_eq!(
+            windows(11, 5, 0, TailPolicy::MergeBelow(3)),
+            vec![0..5, 5..11]
+        );
+        assert_eq!(
+            windows(13, 5, 0, TailPolicy::MergeBelow(3)),
+            vec![0..5, 5..10, 10..13]
+        );
+        assert_eq!(windows(3, 5, 0, TailPolicy::MergeBelow(2)), vec![0..3]);
+        assert_eq!(
+            windows(7, 3, 2, TailPolicy::MergeBelow(2)),
+            vec![0..3, 2..5, 4..7]
+        );
+        assert_eq!(
+            windows(10, 5, 0, TailPolicy::MergeBelow(3)),
+            vec![0..5, 5..10]
+        );
+        assert_eq!(
+            windows(21, 10, 0, TailPolicy::DropBelow(1)),
+            vec![0..10, 10..20, 20..21]
+        );
+        assert_eq!(
+            windows(21, 10, 0, TailPolicy::DropBelow(2)),
+            vec![0..10, 10..20]
+        );
+        assert_eq!(
+            windows(1, 10, 0, TailPolicy::DropBelow(2)),
+            Vec::<Range<usize>>::new()
+        );
+        assert_eq!(
+            windows(0, 10, 0, TailPolicy::MergeBelow(2)),
+            Vec::<Range<usize>>::new()
+        );
+        assert_eq!(
+            windows(5, 0, 0, TailPolicy::MergeBelow(2)),
+            Vec::<Range<usize>>::new()
+        );
+        for tail in [TailPolicy::Keep, TailPolicy::MergeBelow(3)] {
+            assert_eq!(nth_window(13, 5, 0, tail, usize::MAX), None);
+        }
+    }
+
+    #[test]
+    fn split_warnings() {
+        use SplitWarning::*;
+        assert_eq!(
+            SplitWarning::check(10, 5, 0, Tail::Keep),
+            vec![ZeroStepMeansNoOverlap]
+        );
+        assert!(SplitWarning::check(10, 5, 2, Tail::Keep).is_empty());
+        assert_eq!(
+            SplitWarning::check(10, 3, 5, Tail::Drop),
+            vec![StepSkipsElements { gap: 2 }, TailDropped { len: 2 }]
+        );
+        assert_eq!(
+            SplitWarning::check(11, 5, 0, Tail::Drop),
+            vec![ZeroStepMeansNoOverlap, TailDropped { len: 1 }]
+        );
+        assert_eq!(
+            SplitWarning::check(3, 5, 5, Tail::Drop),
+            vec![TailDropped { len: 3 }]
+        );
+        assert_eq!(SplitWarning::check(10, 0, 2, Tail::Keep), vec![ZeroWindow]);
+        assert!(SplitWarning::check(0, 5, 5, Tail::Drop).is_empty());
+    }
+}
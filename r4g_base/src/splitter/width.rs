@@ -0,0 +1,133 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::splitter::from_char::Utf8Splitter;
+
+/// The number of terminal columns `c` takes, from the `unicode-width` crate: 2 for East Asian
+/// Wide and Fullwidth characters, 0 for combining marks, ZWJ, variation selectors and other
+/// zero-width characters, and 1 for everything else, unassigned code points included. Control
+/// characters (`\n` and `\t` included), which `unicode-width` leaves unmeasured, count as 0.
+pub fn char_width(c: char) -> usize {
+    if c.is_control() {
+        return 0;
+    }
+    UnicodeWidthChar::width(c).unwrap_or(0)
+}
+
+/// The number of terminal columns `s` takes, as the sum of `char_width` over its characters.
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+impl<'a> Utf8Splitter<'a> {
+    /// Splits `input` into chunks measured in terminal columns (see `char_width`) rather than
+    /// characters.
+    ///
+    /// - `max_cols`: Maximum number of columns per chunk.
+    /// - `step_cols`: Columns between chunk starts (`0` means `step_cols = max_cols`).
+    /// - `keep_tail`: Keep the last chunk when it is narrower than `max_cols`.
+    ///
+    /// Chunks only end on character boundaries, so a double-width character that does not fit
+    /// moves whole to the next chunk and a chunk may come out a column short. Zero-width
+    /// characters stay with the character before them. A character wider than `max_cols` gets a
+    /// chunk of its own. The start of the next chunk moves the same way, by as many whole
+    /// characters as fit in `step_cols`. Splitting stops with the first chunk that reaches the
+    /// end of `input`.
+    pub fn by_display_width(
+        input: &'a str,
+        max_cols: usize,
+        step_cols: usize,
+        keep_tail: bool,
+    ) -> Vec<&'a str> {
+        let chars: Vec<(usize, usize)> = input
+            .char_indices()
+            .map(|(i, c)| (i, char_width(c)))
+            .collect();
+        let max = max_cols.max(1);
+        let step = if step_cols == 0 { max } else { step_cols };
+        let offset = |k: usize| chars.get(k).map_or(input.len(), |&(i, _)| i);
+
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        while start < chars.len() {
+            let mut end = start;
+            let mut cols = 0usize;
+            while end < chars.len() && (end == start || cols + chars[end].1 <= max) {
+                cols += chars[end].1;
+                end += 1;
+            }
+            let at_end = end == chars.len();
+            if !at_end || cols >= max || keep_tail {
+                out.push(&input[offset(start)..offset(end)]);
+            }
+            if at_end {
+                break;
+            }
+
+            let mut next = start;
+            let mut advanced = 0usize;
+            while next < chars.len() && (next == start || advanced + chars[next].1 <= step) {
+                advanced += chars[next].1;
+                next += 1;
+            }
+            start = next;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths() {
+        assert_eq!(str_width("ab"), 2);
+        assert_eq!(str_width("日本"), 4);
+        assert_eq!(str_width("ｈｉ"), 4);
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("👩\u{200D}💻"), 4);
+        assert_eq!(str_width("a\tb\n\u{1b}"), 2);
+        assert_eq!(char_width('\u{0378}'), 1);
+        assert_eq!(char_width('\u{FEFF}'), 0);
+        assert_eq!(char_width('\u{7f}'), 0);
+        // Hangul conjoining jamo: only the leading consonant takes columns.
+        assert_eq!(str_width("\u{1112}\u{1161}\u{11AB}"), 2);
+        assert_eq!(str_width("\u{1F1FA}\u{1F1F8}"), 2);
+    }
+
+    #[test]
+    fn never_splits_wide_characters() {
+        let s = "ab日本語cd";
+        assert_eq!(
+            Utf8Splitter::by_display_width(s, 3, 0, true),
+            vec!["ab", "日", "本", "語c", "d"]
+        );
+        assert_eq!(
+            Utf8Splitter::by_display_width(s, 4, 0, true),
+            vec!["ab日", "本語", "cd"]
+        );
+        for chunk in Utf8Splitter::by_display_width(s, 5, 2, true) {
+            assert!(str_width(chunk) <= 5, "{chunk}");
+        }
+    }
+
+    #[test]
+    fn overlap_tail_and_zero_width() {
+        let s = "abcdefg";
+        assert_eq!(
+            Utf8Splitter::by_display_width(s, 4, 2, true),
+            vec!["abcd", "cdef", "efg"]
+        );
+        assert_eq!(
+            Utf8Splitter::by_display_width(s, 4, 2, false),
+            vec!["abcd", "cdef"]
+        );
+        let accents = "e\u{301}e\u{301}e\u{301}";
+        assert_eq!(
+            Utf8Splitter::by_display_width(accents, 2, 0, true),
+            vec!["e\u{301}e\u{301}", "e\u{301}"]
+        );
+        assert_eq!(Utf8Splitter::by_display_width("日", 1, 0, true), vec!["日"]);
+        assert!(Utf8Splitter::by_display_width("", 3, 0, true).is_empty());
+    }
+}